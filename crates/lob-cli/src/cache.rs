@@ -4,6 +4,7 @@ use crate::error::{LobError, Result};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// Manages compiled binary cache
 pub struct Cache {
@@ -33,11 +34,19 @@ impl Cache {
         &self.cache_dir
     }
 
-    /// Hash source code to generate cache key
+    /// Hash source code, toolchain and optimization level to generate cache key
+    ///
+    /// `toolchain` identifies the compiler (its `rustc --version` output and
+    /// fixed flags), so upgrading rustc never serves a binary built by an older
+    /// one. The level is part of the key so builds at different levels of the
+    /// same source never overwrite each other.
     #[allow(clippy::unused_self)]
-    pub fn hash_source(&self, source: &str) -> String {
+    pub fn hash_source(&self, source: &str, toolchain: &str, opt_level: u8) -> String {
         let mut hasher = Sha256::new();
         hasher.update(source.as_bytes());
+        hasher.update([0]);
+        hasher.update(toolchain.as_bytes());
+        hasher.update([0, opt_level]);
         format!("{:x}", hasher.finalize())
     }
 
@@ -76,6 +85,79 @@ impl Cache {
         Ok(())
     }
 
+    /// Mark a cached binary as recently used
+    pub fn touch(&self, hash: &str) -> Result<()> {
+        fs::File::options()
+            .write(true)
+            .open(self.binary_path(hash))?
+            .set_modified(SystemTime::now())?;
+        Ok(())
+    }
+
+    /// Evict least-recently-used binaries until the cache is at most `max_bytes`
+    ///
+    /// Recency is the binary's modification time, which [`touch`](Self::touch)
+    /// refreshes on every cache hit. The binary for `keep` (the one about to
+    /// run) is never removed, even if the limit cannot be met without it.
+    /// Returns the hashes that were removed.
+    pub fn evict_to(&self, max_bytes: u64, keep: Option<&str>) -> Result<Vec<String>> {
+        let binaries_dir = self.cache_dir.join("binaries");
+        let mut entries = Vec::new();
+        let mut total_size = 0u64;
+
+        for entry in fs::read_dir(&binaries_dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                total_size += metadata.len();
+                entries.push((metadata.modified()?, metadata.len(), entry.file_name()));
+            }
+        }
+
+        // Oldest first
+        entries.sort();
+
+        let mut evicted = Vec::new();
+        for (_, size, name) in entries {
+            if total_size <= max_bytes {
+                break;
+            }
+            let hash = name.to_string_lossy().into_owned();
+            if keep == Some(hash.as_str()) {
+                continue;
+            }
+            fs::remove_file(binaries_dir.join(&name))?;
+            let _ = fs::remove_file(self.cache_dir.join("sources").join(format!("{}.rs", hash)));
+            total_size -= size;
+            evicted.push(hash);
+        }
+
+        Ok(evicted)
+    }
+
+    /// List cached binaries, most recently used first
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        let binaries_dir = self.cache_dir.join("binaries");
+        let mut entries = Vec::new();
+
+        if binaries_dir.exists() {
+            for entry in fs::read_dir(&binaries_dir)? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if metadata.is_file() {
+                    entries.push(CacheEntry {
+                        hash: entry.file_name().to_string_lossy().into_owned(),
+                        size: metadata.len(),
+                        modified: metadata.modified()?,
+                    });
+                }
+            }
+        }
+
+        entries.sort_by_key(|e| std::cmp::Reverse(e.modified));
+        Ok(entries)
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> Result<CacheStats> {
         let binaries_dir = self.cache_dir.join("binaries");
@@ -99,6 +181,65 @@ impl Cache {
     }
 }
 
+/// Parse a size such as `500M`, `2G`, `64K` or a plain byte count
+pub fn parse_size(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim();
+    let (digits, multiplier) = match s.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&s[..i], 1024),
+        Some((i, 'M' | 'm')) => (&s[..i], 1024 * 1024),
+        Some((i, 'G' | 'g')) => (&s[..i], 1024 * 1024 * 1024),
+        Some((i, 'B' | 'b')) => (&s[..i], 1),
+        _ => (s, 1),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid size '{}' (expected e.g. 500M, 2G, 1048576)", s))
+}
+
+/// A single cached binary
+#[derive(Debug)]
+pub struct CacheEntry {
+    /// Source hash used as the cache key
+    pub hash: String,
+    /// Binary size in bytes
+    pub size: u64,
+    /// Last time the binary was built or used
+    pub modified: SystemTime,
+}
+
+impl CacheEntry {
+    /// Format the time since last use, e.g. `5m ago`
+    pub fn format_age(&self, now: SystemTime) -> String {
+        let secs = now.duration_since(self.modified).map_or(0, |d| d.as_secs());
+        match secs {
+            0..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86400),
+        }
+    }
+}
+
+/// Format a byte count in human-readable form
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
 /// Cache statistics
 #[derive(Debug)]
 pub struct CacheStats {
@@ -111,19 +252,7 @@ pub struct CacheStats {
 impl CacheStats {
     /// Format total size in human-readable format
     pub fn format_size(&self) -> String {
-        const KB: u64 = 1024;
-        const MB: u64 = 1024 * KB;
-        const GB: u64 = 1024 * MB;
-
-        if self.total_size >= GB {
-            format!("{:.2} GB", self.total_size as f64 / GB as f64)
-        } else if self.total_size >= MB {
-            format!("{:.2} MB", self.total_size as f64 / MB as f64)
-        } else if self.total_size >= KB {
-            format!("{:.2} KB", self.total_size as f64 / KB as f64)
-        } else {
-            format!("{} B", self.total_size)
-        }
+        format_bytes(self.total_size)
     }
 }
 
@@ -179,4 +308,140 @@ mod tests {
         assert!(formatted.contains("MB"));
         assert!(formatted.contains("500"));
     }
+
+    #[test]
+    fn hash_depends_on_opt_level() {
+        let cache = Cache {
+            cache_dir: PathBuf::new(),
+        };
+        let source = "fn main() {}";
+        let rustc = "rustc 1.80.0 (051478957 2024-07-21)";
+        assert_eq!(
+            cache.hash_source(source, rustc, 3),
+            cache.hash_source(source, rustc, 3)
+        );
+        assert_ne!(
+            cache.hash_source(source, rustc, 0),
+            cache.hash_source(source, rustc, 3)
+        );
+    }
+
+    #[test]
+    fn hash_depends_on_rustc_version() {
+        let cache = Cache {
+            cache_dir: PathBuf::new(),
+        };
+        let source = "fn main() {}";
+        assert_ne!(
+            cache.hash_source(source, "rustc 1.80.0 (051478957 2024-07-21)", 3),
+            cache.hash_source(source, "rustc 1.81.0 (eeb90cda1 2024-09-04)", 3)
+        );
+    }
+
+    fn temp_cache(name: &str) -> Cache {
+        let cache_dir =
+            std::env::temp_dir().join(format!("lob-cache-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        fs::create_dir_all(cache_dir.join("binaries")).unwrap();
+        fs::create_dir_all(cache_dir.join("sources")).unwrap();
+        Cache { cache_dir }
+    }
+
+    fn store_fake_binary(cache: &Cache, hash: &str, size: usize, age_secs: u64) {
+        let path = cache.binary_path(hash);
+        fs::write(&path, vec![0u8; size]).unwrap();
+        cache.store_source(hash, "fn main() {}").unwrap();
+        let modified = SystemTime::now() - std::time::Duration::from_secs(age_secs);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn evict_removes_oldest_first() {
+        let cache = temp_cache("evict");
+        store_fake_binary(&cache, "old", 100, 300);
+        store_fake_binary(&cache, "middle", 100, 200);
+        store_fake_binary(&cache, "new", 100, 100);
+
+        let evicted = cache.evict_to(150, None).unwrap();
+
+        assert_eq!(evicted, vec!["old".to_string(), "middle".to_string()]);
+        assert!(cache.get_binary("old").is_none());
+        assert!(cache.get_binary("middle").is_none());
+        assert!(cache.get_binary("new").is_some());
+        assert!(!cache.cache_dir.join("sources").join("old.rs").exists());
+        assert_eq!(cache.stats().unwrap().total_size, 100);
+
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn touch_protects_recently_used_entry() {
+        let cache = temp_cache("touch");
+        store_fake_binary(&cache, "a", 100, 300);
+        store_fake_binary(&cache, "b", 100, 200);
+        cache.touch("a").unwrap();
+
+        let evicted = cache.evict_to(100, None).unwrap();
+
+        assert_eq!(evicted, vec!["b".to_string()]);
+        assert!(cache.get_binary("a").is_some());
+
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn evict_never_removes_kept_binary() {
+        let cache = temp_cache("keep");
+        store_fake_binary(&cache, "current", 100, 300);
+        store_fake_binary(&cache, "other", 100, 200);
+
+        // Limit is smaller than the kept binary alone
+        let evicted = cache.evict_to(10, Some("current")).unwrap();
+
+        assert_eq!(evicted, vec!["other".to_string()]);
+        assert!(cache.get_binary("current").is_some());
+
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn evict_under_limit_is_noop() {
+        let cache = temp_cache("noop");
+        store_fake_binary(&cache, "a", 10, 10);
+        assert!(cache.evict_to(1024, None).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("500M"), Ok(500 * 1024 * 1024));
+        assert_eq!(parse_size("2g"), Ok(2 * 1024 * 1024 * 1024));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn entries_lists_binaries_by_recency() {
+        let cache = temp_cache("entries");
+        store_fake_binary(&cache, "older", 10, 600);
+        store_fake_binary(&cache, "newer", 25, 60);
+
+        let entries = cache.entries().unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].hash, "newer");
+        assert_eq!(entries[0].size, 25);
+        assert_eq!(entries[1].hash, "older");
+        assert_eq!(entries[1].size, 10);
+        assert_eq!(entries[1].format_age(SystemTime::now()), "10m ago");
+
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
 }
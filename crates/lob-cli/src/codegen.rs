@@ -1,8 +1,9 @@
 //! Code generation for lob expressions
 
-use crate::error::Result;
+use crate::error::{LobError, Result};
 use crate::input::{InputFormat, InputSource};
-use crate::output::OutputFormat;
+use crate::output::{OutputFormat, TableStyle};
+use crate::tokenizer::{tokenize, Token, TokenKind};
 
 /// Generates Rust source code from a lob expression
 pub struct CodeGenerator {
@@ -10,8 +11,25 @@ pub struct CodeGenerator {
     input_source: InputSource,
     output_format: OutputFormat,
     enable_stats: bool,
+    stats_interval_ms: u64,
+    table_style: TableStyle,
+    count_only: bool,
+    limit: Option<usize>,
 }
 
+/// Names the generated program binds around the user expression
+pub const RESERVED_IDENTIFIERS: &[&str] = &[
+    "result",
+    "stdin_data",
+    "item_count",
+    "start_time",
+    "last_print",
+    "print_interval_ms",
+];
+
+/// Minimum milliseconds between progress lines when `--stats` is on
+pub const DEFAULT_STATS_INTERVAL_MS: u64 = 500;
+
 impl CodeGenerator {
     /// Create a new code generator for the given expression
     pub fn new(
@@ -25,11 +43,77 @@ impl CodeGenerator {
             input_source,
             output_format,
             enable_stats,
+            stats_interval_ms: DEFAULT_STATS_INTERVAL_MS,
+            table_style: TableStyle::default(),
+            count_only: false,
+            limit: None,
+        }
+    }
+
+    /// Set the minimum time between progress lines when stats are enabled
+    #[must_use]
+    pub fn with_stats_interval_ms(mut self, stats_interval_ms: u64) -> Self {
+        self.stats_interval_ms = stats_interval_ms;
+        self
+    }
+
+    /// Set the border style used for table output
+    #[must_use]
+    pub fn with_table_style(mut self, table_style: TableStyle) -> Self {
+        self.table_style = table_style;
+        self
+    }
+
+    /// Print only the number of output items instead of the items themselves
+    ///
+    /// No serializer is generated; a terminal value counts as one item.
+    #[must_use]
+    pub fn with_count_only(mut self, count_only: bool) -> Self {
+        self.count_only = count_only;
+        self
+    }
+
+    /// Cap the number of output items (iterator results only)
+    #[must_use]
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Whether a requested limit has no effect because the result is a single value
+    pub fn limit_ignored(&self) -> bool {
+        self.limit.is_some() && self.has_terminal_operation()
+    }
+
+    /// Reject expressions that use a name the generated code binds
+    ///
+    /// Field and method names (after a `.`) and string contents are fine.
+    pub fn validate(&self) -> Result<()> {
+        let mut after_dot = false;
+        for token in tokenize(&self.expression) {
+            if token.is_trivia() {
+                continue;
+            }
+            if token.kind == TokenKind::Ident
+                && !after_dot
+                && RESERVED_IDENTIFIERS.contains(&token.text)
+            {
+                return Err(LobError::InvalidExpression(format!(
+                    "`{}` is reserved by the generated program; please rename it",
+                    token.text
+                )));
+            }
+            after_dot = token.is_punct('.');
         }
+        Ok(())
     }
 
     /// Generate complete Rust program from expression
-    pub fn generate(&self) -> Result<String> {
+    ///
+    /// Also returns where the user expression landed in the program, so
+    /// compiler diagnostics can be pointed back at the text the user typed.
+    pub fn generate(&self) -> Result<(String, ExpressionSpan)> {
+        self.validate()?;
         let mut code = String::new();
 
         // Add prelude imports
@@ -38,21 +122,27 @@ impl CodeGenerator {
 
         // Add stats tracking imports if enabled
         if self.enable_stats {
-            code.push_str("use std::sync::atomic::{AtomicUsize, Ordering};\n");
+            code.push_str("use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};\n");
             code.push_str("use std::sync::Arc;\n");
             code.push_str("use std::time::Instant;\n");
         }
 
         // Add serde_json import if using JSON output (from lob_prelude re-export)
-        if matches!(
-            self.output_format,
-            OutputFormat::Json | OutputFormat::JsonLines
-        ) {
+        if !self.count_only
+            && matches!(
+                self.output_format,
+                OutputFormat::Json | OutputFormat::JsonLines
+            )
+        {
             code.push_str("use lob_prelude::serde_json;\n");
         }
 
+        if !self.count_only && matches!(self.output_format, OutputFormat::Yaml) {
+            code.push_str("use lob_prelude::serde_yaml;\n");
+        }
+
         // Add tabled import if using Table output
-        if matches!(self.output_format, OutputFormat::Table) {
+        if !self.count_only && matches!(self.output_format, OutputFormat::Table) {
             code.push_str("use lob_prelude::tabled::builder::Builder;\n");
             code.push_str("use lob_prelude::tabled::settings::Style;\n");
         }
@@ -64,47 +154,55 @@ impl CodeGenerator {
         if self.enable_stats {
             code.push_str("    let start_time = Instant::now();\n");
             code.push_str("    let item_count = Arc::new(AtomicUsize::new(0));\n");
-            code.push_str("    let last_print = Arc::new(AtomicUsize::new(0));\n");
-            code.push_str("    let print_interval = 10000; // Print every 10k items\n");
+            code.push_str("    let last_print = Arc::new(AtomicU64::new(0));\n");
+            code.push_str(&format!(
+                "    let print_interval_ms: u64 = {}; // Print at most every {} ms\n",
+                self.stats_interval_ms, self.stats_interval_ms
+            ));
             code.push('\n');
         }
 
-        // Check if expression uses stdin (starts with '_')
-        let uses_stdin = self.expression.trim().starts_with('_');
+        // Each standalone `_` stands for the input stream
+        let placeholders = stdin_placeholders(&self.expression);
+        let uses_stdin = !placeholders.is_empty();
 
         // Generate input based on format and source
-        let expression = if uses_stdin {
+        let (expression, replacement) = if uses_stdin {
             self.generate_input(&mut code);
             if self.enable_stats {
-                // Wrap iterator with stats tracking
-                code.push_str("    let stdin_data = {\n");
-                code.push_str("        let counter = item_count.clone();\n");
-                code.push_str("        let last = last_print.clone();\n");
-                code.push_str("        let start = start_time;\n");
-                code.push_str("        stdin_data.map(move |item| {\n");
-                code.push_str(
-                    "            let count = counter.fetch_add(1, Ordering::Relaxed) + 1;\n",
-                );
-                code.push_str("            let last_val = last.load(Ordering::Relaxed);\n");
-                code.push_str("            if count - last_val >= print_interval {\n");
-                code.push_str("                let elapsed = start.elapsed().as_secs_f64();\n");
-                code.push_str("                let throughput = count as f64 / elapsed;\n");
-                code.push_str(
-                    "                eprintln!(\"\\r[Stats] Items: {} | Throughput: {:.0} items/s | Elapsed: {:.1}s\", count, throughput, elapsed);\n",
-                );
-                code.push_str("                last.store(count, Ordering::Relaxed);\n");
-                code.push_str("            }\n");
-                code.push_str("            item\n");
-                code.push_str("        })\n");
-                code.push_str("    };\n");
+                Self::generate_stats_wrapper(&mut code);
             }
-            self.expression.replacen('_', "stdin_data", 1)
+            let replacement = if placeholders.len() == 1 {
+                "stdin_data"
+            } else {
+                // Several uses (e.g. a self-join): buffer once, clone per use
+                code.push_str("    let stdin_data: Vec<_> = stdin_data.collect();\n");
+                "lob(stdin_data.clone())"
+            };
+            (
+                substitute(&self.expression, &placeholders, replacement),
+                replacement.len(),
+            )
         } else {
-            self.expression.clone()
+            (self.expression.clone(), 0)
         };
 
-        // User expression
-        code.push_str(&format!("    let result = {};\n", expression));
+        // User expression; with statements it becomes a block whose final
+        // expression is the result
+        let expression = expression.trim_end().trim_end_matches(';').trim_end();
+        let offset = if final_expression(expression).len() < expression.len() {
+            code.push_str("    let result = {\n        ");
+            let offset = code.len();
+            code.push_str(&format!("{}\n", expression));
+            code.push_str("    };\n");
+            offset
+        } else {
+            code.push_str("    let result = ");
+            let offset = code.len();
+            code.push_str(&format!("{};\n", expression));
+            offset
+        };
+        let span = ExpressionSpan::new(&code, offset, expression, &placeholders, replacement);
 
         // Generate output based on format
         self.generate_output(&mut code);
@@ -122,44 +220,141 @@ impl CodeGenerator {
 
         code.push_str("}\n");
 
-        Ok(code)
+        Ok((code, span))
+    }
+
+    /// Wrap `stdin_data` so every item bumps the counter and progress is
+    /// printed at most once per `print_interval_ms`
+    fn generate_stats_wrapper(code: &mut String) {
+        code.push_str("    let stdin_data = {\n");
+        code.push_str("        let counter = item_count.clone();\n");
+        code.push_str("        let last = last_print.clone();\n");
+        code.push_str("        let start = start_time;\n");
+        code.push_str("        stdin_data.map(move |item| {\n");
+        code.push_str("            let count = counter.fetch_add(1, Ordering::Relaxed) + 1;\n");
+        code.push_str("            let now_ms = start.elapsed().as_millis() as u64;\n");
+        code.push_str(
+            "            if now_ms - last.load(Ordering::Relaxed) >= print_interval_ms {\n",
+        );
+        code.push_str("                let elapsed = now_ms as f64 / 1000.0;\n");
+        code.push_str("                let throughput = count as f64 / elapsed;\n");
+        code.push_str(
+            "                eprintln!(\"\\r[Stats] Items: {} | Throughput: {:.0} items/s | Elapsed: {:.1}s\", count, throughput, elapsed);\n",
+        );
+        code.push_str("                last.store(now_ms, Ordering::Relaxed);\n");
+        code.push_str("            }\n");
+        code.push_str("            item\n");
+        code.push_str("        })\n");
+        code.push_str("    };\n");
+    }
+
+    /// Human-readable summary of how the expression would be run
+    ///
+    /// Covers the decisions code generation makes without compiling anything;
+    /// `cache_hash` is the key the compiled binary would be cached under.
+    pub fn explain(&self, cache_hash: &str) -> String {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let placeholders = stdin_placeholders(&self.expression).len();
+        let reads_input = match (placeholders, self.input_source.is_stdin()) {
+            (0, _) => "no".to_string(),
+            (_, true) => "yes (stdin)".to_string(),
+            (_, false) => format!(
+                "yes ({})",
+                self.input_source
+                    .files
+                    .iter()
+                    .map(|f| f.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let mut out = String::new();
+        out.push_str(&format!("expression: {}\n", self.expression));
+        out.push_str(&format!(
+            "input format: {}\n",
+            self.input_source.format.name()
+        ));
+        out.push_str(&format!("reads input: {}\n", reads_input));
+        if placeholders > 1 {
+            out.push_str(&format!(
+                "input placeholders: {} (input is buffered in memory)\n",
+                placeholders
+            ));
+        }
+        out.push_str(&format!(
+            "terminal operation: {}\n",
+            yes_no(self.has_terminal_operation())
+        ));
+        out.push_str(&format!(
+            "output: {}\n",
+            if self.has_terminal_operation() {
+                "single value"
+            } else {
+                "one item per element"
+            }
+        ));
+        let output_format = if self.count_only {
+            "count"
+        } else {
+            self.output_format.name()
+        };
+        out.push_str(&format!("output format: {}\n", output_format));
+        if let Some(limit) = self.limit {
+            out.push_str(&format!("limit: {}\n", limit));
+        }
+        out.push_str(&format!("stats: {}\n", yes_no(self.enable_stats)));
+        out.push_str(&format!("cache hash: {}\n", cache_hash));
+        out
     }
 
     /// Generate input code based on input source and format
     fn generate_input(&self, code: &mut String) {
-        match self.input_source.format {
-            InputFormat::Lines => {
-                if self.input_source.is_stdin() {
-                    code.push_str("    let stdin_data = input();\n");
-                } else {
-                    code.push_str("    let files: Vec<_> = std::env::args().skip(1).map(|p| std::path::PathBuf::from(p)).collect();\n");
-                    code.push_str("    let stdin_data = input_from_files(&files);\n");
-                }
-            }
-            InputFormat::Csv => {
-                if self.input_source.is_stdin() {
-                    code.push_str("    let stdin_data = input_csv();\n");
-                } else {
-                    code.push_str("    let files: Vec<_> = std::env::args().skip(1).map(|p| std::path::PathBuf::from(p)).collect();\n");
-                    code.push_str("    let stdin_data = input_csv_from_files(&files);\n");
-                }
-            }
-            InputFormat::Tsv => {
-                if self.input_source.is_stdin() {
-                    code.push_str("    let stdin_data = input_tsv();\n");
-                } else {
-                    code.push_str("    let files: Vec<_> = std::env::args().skip(1).map(|p| std::path::PathBuf::from(p)).collect();\n");
-                    code.push_str("    let stdin_data = input_tsv_from_files(&files);\n");
-                }
-            }
-            InputFormat::JsonLines => {
-                if self.input_source.is_stdin() {
-                    code.push_str("    let stdin_data = input_json();\n");
-                } else {
-                    code.push_str("    let files: Vec<_> = std::env::args().skip(1).map(|p| std::path::PathBuf::from(p)).collect();\n");
-                    code.push_str("    let stdin_data = input_json_from_files(&files);\n");
-                }
-            }
+        let (stdin_fn, files_fn, options) = match &self.input_source.format {
+            InputFormat::Lines => ("input", "input_from_files", None),
+            InputFormat::Csv if !self.input_source.header => (
+                "input_csv_headerless",
+                "input_csv_headerless_from_files",
+                None,
+            ),
+            InputFormat::Tsv if !self.input_source.header => (
+                "input_tsv_headerless",
+                "input_tsv_headerless_from_files",
+                None,
+            ),
+            InputFormat::Csv => ("input_csv", "input_csv_from_files", None),
+            InputFormat::Tsv => ("input_tsv", "input_tsv_from_files", None),
+            InputFormat::JsonLines => ("input_json", "input_json_from_files", None),
+            InputFormat::Delimited(delimiter) => (
+                "input_delimited",
+                "input_delimited_from_files",
+                Some(format!("{:?}", delimiter)),
+            ),
+            InputFormat::Split(separator) => (
+                "input_split",
+                "input_split_from_files",
+                Some(format!("{:?}", separator)),
+            ),
+            InputFormat::FixedWidth(widths) => (
+                "input_fixed_width",
+                "input_fixed_width_from_files",
+                Some(format!("&{:?}", widths)),
+            ),
+        };
+
+        if self.input_source.is_stdin() {
+            code.push_str(&format!(
+                "    let stdin_data = {}({});\n",
+                stdin_fn,
+                options.as_deref().unwrap_or_default()
+            ));
+        } else {
+            code.push_str("    let files = input_files();\n");
+            code.push_str(&format!(
+                "    let stdin_data = {}(&files{});\n",
+                files_fn,
+                options.map(|o| format!(", {}", o)).unwrap_or_default()
+            ));
         }
     }
 
@@ -167,6 +362,20 @@ impl CodeGenerator {
     fn generate_output(&self, code: &mut String) {
         let is_iter = !self.has_terminal_operation();
 
+        if let (Some(limit), true) = (self.limit, is_iter) {
+            code.push_str(&format!("    let result = result.take({});\n", limit));
+        }
+
+        if self.count_only {
+            if is_iter {
+                code.push_str("    println!(\"{}\", result.count());\n");
+            } else {
+                code.push_str("    let _ = result;\n");
+                code.push_str("    println!(\"1\");\n");
+            }
+            return;
+        }
+
         match self.output_format {
             OutputFormat::Debug => {
                 if is_iter {
@@ -210,62 +419,832 @@ impl CodeGenerator {
                     code.push_str("    output_csv(&[result]);\n");
                 }
             }
-            OutputFormat::Table => {
+            OutputFormat::Parquet => {
                 if is_iter {
                     code.push_str("    let items: Vec<_> = result.collect();\n");
-                    code.push_str("    if !items.is_empty() {\n");
-                    code.push_str("        let mut builder = Builder::default();\n");
-                    code.push_str("        // Extract headers from first item\n");
-                    code.push_str("        let mut headers: Vec<_> = items[0].keys().collect();\n");
-                    code.push_str("        headers.sort();\n");
-                    code.push_str(
-                        "        builder.push_record(headers.iter().map(|k| k.as_str()));\n",
-                    );
-                    code.push_str("        // Add data rows\n");
-                    code.push_str("        for item in &items {\n");
-                    code.push_str("            let row: Vec<_> = headers.iter().map(|k| item.get(*k).map(|v| v.as_str()).unwrap_or(\"\")).collect();\n");
-                    code.push_str("            builder.push_record(row);\n");
-                    code.push_str("        }\n");
-                    code.push_str(
-                        "        let table = builder.build().with(Style::rounded()).to_string();\n",
-                    );
-                    code.push_str("        println!(\"{}\", table);\n");
-                    code.push_str("    }\n");
+                    code.push_str("    output_parquet(&items);\n");
                 } else {
-                    code.push_str("    let mut builder = Builder::default();\n");
-                    code.push_str("    let mut headers: Vec<_> = result.keys().collect();\n");
-                    code.push_str("    headers.sort();\n");
-                    code.push_str("    builder.push_record(headers.iter().map(|k| k.as_str()));\n");
-                    code.push_str("    let row: Vec<_> = headers.iter().map(|k| result.get(*k).map(|v| v.as_str()).unwrap_or(\"\")).collect();\n");
-                    code.push_str("    builder.push_record(row);\n");
-                    code.push_str(
-                        "    let table = builder.build().with(Style::rounded()).to_string();\n",
-                    );
-                    code.push_str("    println!(\"{}\", table);\n");
+                    code.push_str("    output_parquet(&[result]);\n");
+                }
+            }
+            OutputFormat::Yaml => {
+                // A sequence for iterators, a single document for values
+                if is_iter {
+                    code.push_str("    let items: Vec<_> = result.collect();\n");
+                    code.push_str("    print!(\"{}\", serde_yaml::to_string(&items).unwrap());\n");
+                } else {
+                    code.push_str("    print!(\"{}\", serde_yaml::to_string(&result).unwrap());\n");
                 }
             }
+            OutputFormat::Table => self.generate_table_output(code, is_iter),
+            OutputFormat::Markdown => Self::generate_markdown_output(code, is_iter),
+        }
+    }
+
+    /// Emit `headers`: the first row's keys, sorted so column order is stable
+    fn push_sorted_headers(code: &mut String, first_row: &str, indent: &str) {
+        code.push_str(&format!(
+            "{indent}let mut headers: Vec<_> = {first_row}.keys().collect();\n"
+        ));
+        code.push_str(&format!("{indent}headers.sort();\n"));
+    }
+
+    /// Generate output code rendering map rows as a GitHub-flavored Markdown table
+    fn generate_markdown_output(code: &mut String, is_iter: bool) {
+        if is_iter {
+            code.push_str("    let items: Vec<_> = result.collect();\n");
+        } else {
+            code.push_str("    let items = vec![result];\n");
+        }
+        code.push_str("    if !items.is_empty() {\n");
+        Self::push_sorted_headers(code, "items[0]", "        ");
+        code.push_str("        let escape = |s: &str| s.replace('|', \"\\\\|\");\n");
+        code.push_str(
+            "        let header: Vec<_> = headers.iter().map(|k| escape(k)).collect();\n",
+        );
+        code.push_str("        println!(\"| {} |\", header.join(\" | \"));\n");
+        code.push_str("        println!(\"|{}\", \" --- |\".repeat(headers.len()));\n");
+        code.push_str("        for item in &items {\n");
+        code.push_str("            let row: Vec<_> = headers.iter().map(|k| escape(item.get(*k).map(|v| v.as_str()).unwrap_or(\"\"))).collect();\n");
+        code.push_str("            println!(\"| {} |\", row.join(\" | \"));\n");
+        code.push_str("        }\n");
+        code.push_str("    }\n");
+    }
+
+    /// Generate output code rendering map rows as a table
+    fn generate_table_output(&self, code: &mut String, is_iter: bool) {
+        let style = self.table_style.constructor();
+        if is_iter {
+            code.push_str("    let items: Vec<_> = result.collect();\n");
+            code.push_str("    if !items.is_empty() {\n");
+            code.push_str("        let mut builder = Builder::default();\n");
+            code.push_str("        // Extract headers from first item\n");
+            Self::push_sorted_headers(code, "items[0]", "        ");
+            code.push_str("        builder.push_record(headers.iter().map(|k| k.as_str()));\n");
+            code.push_str("        // Add data rows\n");
+            code.push_str("        for item in &items {\n");
+            code.push_str("            let row: Vec<_> = headers.iter().map(|k| item.get(*k).map(|v| v.as_str()).unwrap_or(\"\")).collect();\n");
+            code.push_str("            builder.push_record(row);\n");
+            code.push_str("        }\n");
+            code.push_str(&format!(
+                "        let table = builder.build().with({style}).to_string();\n"
+            ));
+            code.push_str("        println!(\"{}\", table);\n");
+            code.push_str("    }\n");
+        } else {
+            code.push_str("    let mut builder = Builder::default();\n");
+            Self::push_sorted_headers(code, "result", "    ");
+            code.push_str("    builder.push_record(headers.iter().map(|k| k.as_str()));\n");
+            code.push_str("    let row: Vec<_> = headers.iter().map(|k| result.get(*k).map(|v| v.as_str()).unwrap_or(\"\")).collect();\n");
+            code.push_str("    builder.push_record(row);\n");
+            code.push_str(&format!(
+                "    let table = builder.build().with({style}).to_string();\n"
+            ));
+            code.push_str("    println!(\"{}\", table);\n");
         }
     }
 
     /// Check if expression has a terminal operation
+    ///
+    /// Only method calls at the top level of the chain count, so a terminal
+    /// name inside a closure (`.map(|l| l.len().max(1))`) or a string literal
+    /// (`.filter(|l| l.contains(".count()"))`) does not.
     fn has_terminal_operation(&self) -> bool {
-        let terminals = [
-            ".collect(",
-            ".count()",
-            ".sum(",
-            ".sum::",
-            ".min()",
-            ".max()",
-            ".reduce(",
-            ".fold(",
-            ".fold_left(",
-            ".first()",
-            ".last()",
-            ".to_list()",
-            ".any(",
-            ".all(",
+        const TERMINALS: &[&str] = &[
+            "collect",
+            "count",
+            "sum",
+            "min",
+            "max",
+            "min_by",
+            "max_by",
+            "reduce",
+            "fold",
+            "fold_left",
+            "try_fold",
+            "fold_while",
+            "first",
+            "last",
+            "to_list",
+            "concat",
+            "collect_result",
+            "try_map",
+            "any",
+            "all",
+            "find_map",
+            "index_of",
+            "nested_counts",
+            "group_reduce",
+            "group_into_map",
+            "sum_by_key",
+            "ngram_counts",
+            "parse_kv_map",
+            "diff_lines",
+            "unzip",
+            "collect_columns",
+            "to_map",
+            "to_set",
+            "join_str",
+            "mean",
+            "variance",
+            "stddev",
+            "percentile",
+            "median",
+            "reservoir_sample",
+            "reservoir_sample_seeded",
+        ];
+
+        let expression = self.expression.trim_end().trim_end_matches(';');
+        top_level_methods(final_expression(expression))
+            .iter()
+            .any(|m| TERMINALS.contains(m))
+    }
+}
+
+/// Location of the user expression inside the generated program
+///
+/// Records where the expression starts and which `_` placeholders were
+/// rewritten, so rustc line/column positions can be mapped back onto the
+/// original expression text.
+#[derive(Debug, Clone)]
+pub struct ExpressionSpan {
+    /// 1-based line of the generated source the expression starts on
+    pub line: usize,
+    /// 1-based column of the expression's first character on that line
+    pub column: usize,
+    /// First line of the expression as emitted (after substitution)
+    generated: String,
+    /// Placeholder ranges in the original expression
+    placeholders: Vec<std::ops::Range<usize>>,
+    /// Length of the text each placeholder was replaced with
+    replacement_len: usize,
+}
+
+impl ExpressionSpan {
+    fn new(
+        code: &str,
+        offset: usize,
+        expression: &str,
+        placeholders: &[std::ops::Range<usize>],
+        replacement_len: usize,
+    ) -> Self {
+        let before = &code[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            generated: expression.lines().next().unwrap_or_default().to_string(),
+            placeholders: placeholders.to_vec(),
+            replacement_len,
+        }
+    }
+
+    /// Map a 1-based rustc `line:column` to a byte offset in the original expression
+    ///
+    /// Returns `None` for positions outside the expression's first line.
+    /// Positions inside a substituted placeholder map to the `_` itself.
+    pub fn original_offset(&self, line: usize, column: usize) -> Option<usize> {
+        if line != self.line || column < self.column {
+            return None;
+        }
+        let generated = self
+            .generated
+            .char_indices()
+            .nth(column - self.column)
+            .map(|(i, _)| i)?;
+
+        let mut shift = 0isize;
+        for range in &self.placeholders {
+            let start = range.start.checked_add_signed(shift)?;
+            if generated < start {
+                break;
+            }
+            if generated < start + self.replacement_len {
+                return Some(range.start);
+            }
+            shift += self.replacement_len.cast_signed() - range.len().cast_signed();
+        }
+        generated.checked_add_signed(-shift)
+    }
+}
+
+/// Byte ranges of the `_` tokens that stand for the input stream
+///
+/// A bare `_` is a placeholder where an expression is expected: followed by a
+/// method call (`_.take(3)`), passed as an argument (`join_inner(_, ..)`) or
+/// standing alone. Identifiers containing underscores (`my_var`, `_x`) and
+/// wildcard uses in closure parameters, `let`/`for` patterns and type
+/// arguments (`Vec<_>`) are left alone.
+fn stdin_placeholders(expression: &str) -> Vec<std::ops::Range<usize>> {
+    let mut offset = 0;
+    let tokens: Vec<(usize, Token<'_>)> = tokenize(expression)
+        .into_iter()
+        .filter_map(|t| {
+            let start = offset;
+            offset += t.text.len();
+            (!t.is_trivia()).then_some((start, t))
+        })
+        .collect();
+
+    let ends_expression = |t: &Token<'_>| {
+        matches!(t.kind, TokenKind::Literal | TokenKind::Lifetime)
+            || (t.kind == TokenKind::Ident && t.text != "move")
+            || t.is_punct(')')
+            || t.is_punct(']')
+            || t.is_punct('}')
+            || t.is_punct('?')
+    };
+
+    let mut ranges = Vec::new();
+    let mut in_closure_params = false;
+    let mut in_binding_pattern = false;
+    let mut generic_depth = 0usize;
+
+    for (i, (start, token)) in tokens.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|j| &tokens[j].1);
+        let next = tokens.get(i + 1).map(|(_, t)| t);
+
+        if token.is_punct('|') {
+            if in_closure_params {
+                in_closure_params = false;
+            } else if !prev.is_some_and(ends_expression) {
+                in_closure_params = true;
+            }
+            continue;
+        }
+        if token.kind == TokenKind::Ident && matches!(token.text, "let" | "for") {
+            in_binding_pattern = true;
+            continue;
+        }
+        if in_binding_pattern
+            && ((token.is_punct('=') && !next.is_some_and(|t| t.is_punct('=')))
+                || (token.kind == TokenKind::Ident && token.text == "in"))
+        {
+            in_binding_pattern = false;
+            continue;
+        }
+        if token.is_punct('<') && (generic_depth > 0 || prev.is_some_and(|t| t.is_punct(':'))) {
+            generic_depth += 1;
+            continue;
+        }
+        if token.is_punct('>') && generic_depth > 0 {
+            generic_depth -= 1;
+            continue;
+        }
+
+        if token.kind != TokenKind::Ident
+            || token.text != "_"
+            || in_closure_params
+            || in_binding_pattern
+            || generic_depth > 0
+        {
+            continue;
+        }
+
+        let starts_expression = prev.is_none_or(|t| {
+            t.is_punct('(')
+                || t.is_punct(',')
+                || t.is_punct('=')
+                || t.is_punct('{')
+                || t.is_punct(';')
+        });
+        let used_as_value = next.is_none_or(|t| {
+            t.is_punct('.')
+                || t.is_punct(',')
+                || t.is_punct(')')
+                || t.is_punct(';')
+                || t.is_punct('}')
+        });
+        if starts_expression && used_as_value {
+            ranges.push(*start..*start + token.text.len());
+        }
+    }
+    ranges
+}
+
+/// Replace each byte range of `expression` with `replacement`
+fn substitute(expression: &str, ranges: &[std::ops::Range<usize>], replacement: &str) -> String {
+    let mut out = String::with_capacity(expression.len());
+    let mut last = 0;
+    for range in ranges {
+        out.push_str(&expression[last..range.start]);
+        out.push_str(replacement);
+        last = range.end;
+    }
+    out.push_str(&expression[last..]);
+    out
+}
+
+/// The part of the user code after its last top-level `;`
+///
+/// For a plain expression this is the whole input; for `let n = 5; _.take(n)`
+/// it is `_.take(n)`.
+fn final_expression(code: &str) -> &str {
+    let mut offset = 0;
+    let mut depth = 0usize;
+    let mut split = 0;
+    for token in tokenize(code) {
+        offset += token.text.len();
+        if token.is_punct('(') || token.is_punct('[') || token.is_punct('{') {
+            depth += 1;
+        } else if token.is_punct(')') || token.is_punct(']') || token.is_punct('}') {
+            depth = depth.saturating_sub(1);
+        } else if token.is_punct(';') && depth == 0 {
+            split = offset;
+        }
+    }
+    code[split..].trim_start()
+}
+
+/// Names of methods called at nesting depth 0 of an expression
+///
+/// Literals and comments are skipped, and calls inside parentheses, brackets
+/// or braces (closure bodies, arguments) are ignored.
+fn top_level_methods(expression: &str) -> Vec<&str> {
+    let tokens: Vec<Token<'_>> = tokenize(expression)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    let mut methods = Vec::new();
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Punct if token.text == "(" || token.text == "[" || token.text == "{" => {
+                depth += 1;
+            }
+            TokenKind::Punct if token.text == ")" || token.text == "]" || token.text == "}" => {
+                depth = depth.saturating_sub(1);
+            }
+            TokenKind::Punct if token.text == "." && depth == 0 => {
+                // `.name(` or `.name::<T>(`
+                if let [name, next, ..] = &tokens[i + 1..] {
+                    if name.kind == TokenKind::Ident && (next.is_punct('(') || next.is_punct(':')) {
+                        methods.push(name.text);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    methods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(expression: &str) -> (String, ExpressionSpan) {
+        CodeGenerator::new(
+            expression.to_string(),
+            InputSource::new(vec![], InputFormat::Lines),
+            OutputFormat::Debug,
+            false,
+        )
+        .generate()
+        .unwrap()
+    }
+
+    fn generate(expression: &str, output_format: OutputFormat) -> String {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        CodeGenerator::new(expression.to_string(), input, output_format, false)
+            .generate()
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn jsonl_output_serializes_each_item() {
+        let code = generate("_.take(3)", OutputFormat::JsonLines);
+        assert!(code.contains("use lob_prelude::serde_json;"));
+        assert!(code.contains("for item in result {"));
+        assert!(code.contains("serde_json::to_string(&item)"));
+    }
+
+    #[test]
+    fn json_output_collects_iterator() {
+        let code = generate("_.take(3)", OutputFormat::Json);
+        assert!(code.contains("serde_json::to_string_pretty(&items)"));
+    }
+
+    #[test]
+    fn json_output_terminal_serializes_result() {
+        let code = generate("_.count()", OutputFormat::JsonLines);
+        assert!(code.contains("serde_json::to_string(&result)"));
+    }
+
+    #[test]
+    fn debug_output_has_no_serde() {
+        let code = generate("_.take(3)", OutputFormat::Debug);
+        assert!(!code.contains("serde_json"));
+        assert!(code.contains("println!(\"{:?}\", item);"));
+    }
+
+    #[test]
+    fn csv_output_uses_prelude_writer() {
+        let code = generate("_", OutputFormat::Csv);
+        assert!(code.contains("output_csv(&items);"));
+    }
+
+    #[test]
+    fn parquet_output_uses_prelude_writer() {
+        let code = generate("_.take(2)", OutputFormat::Parquet);
+        assert!(code.contains("let items: Vec<_> = result.collect();"));
+        assert!(code.contains("output_parquet(&items);"));
+        let code = generate("_.count()", OutputFormat::Parquet);
+        assert!(code.contains("output_parquet(&[result]);"));
+    }
+
+    #[test]
+    fn yaml_output_uses_serde_yaml() {
+        let code = generate("_.take(2)", OutputFormat::Yaml);
+        assert!(code.contains("use lob_prelude::serde_yaml;"));
+        assert!(code.contains("serde_yaml::to_string(&items)"));
+        let code = generate("_.count()", OutputFormat::Yaml);
+        assert!(code.contains("serde_yaml::to_string(&result)"));
+    }
+
+    #[test]
+    fn markdown_output_builds_separator_row() {
+        let code = generate("_.take(2)", OutputFormat::Markdown);
+        assert!(code.contains("let mut headers: Vec<_> = items[0].keys().collect();"));
+        assert!(code.contains("headers.sort();"));
+        assert!(code.contains(r#"println!("|{}", " --- |".repeat(headers.len()));"#));
+        assert!(code.contains(r#"println!("| {} |", row.join(" | "));"#));
+        assert!(!code.contains("tabled"));
+    }
+
+    #[test]
+    fn markdown_output_single_row_for_terminal() {
+        let code = generate("_.first().unwrap()", OutputFormat::Markdown);
+        assert!(code.contains("let items = vec![result];"));
+    }
+
+    #[test]
+    fn table_style_defaults_to_rounded() {
+        let code = generate("_.take(2)", OutputFormat::Table);
+        assert!(code.contains(".with(Style::rounded())"));
+    }
+
+    #[test]
+    fn table_style_selects_tabled_style() {
+        let input = InputSource::new(Vec::new(), InputFormat::Csv);
+        let style = TableStyle::from_str("ascii").unwrap();
+        for expr in ["_.take(2)", "_.first().unwrap()"] {
+            let code =
+                CodeGenerator::new(expr.to_string(), input.clone(), OutputFormat::Table, false)
+                    .with_table_style(style)
+                    .generate()
+                    .unwrap()
+                    .0;
+            assert!(code.contains(".with(Style::ascii())"), "{expr}");
+            assert!(!code.contains("Style::rounded()"), "{expr}");
+        }
+    }
+
+    #[test]
+    fn table_style_rejects_unknown_names() {
+        assert_eq!(TableStyle::from_str("bogus"), None);
+        for name in TableStyle::NAMES {
+            assert!(TableStyle::from_str(name).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn input_format_selects_prelude_helper() {
+        let cases = [
+            (InputFormat::Lines, "input()"),
+            (InputFormat::Csv, "input_csv()"),
+            (InputFormat::Tsv, "input_tsv()"),
+            (InputFormat::JsonLines, "input_json()"),
+            (InputFormat::Delimited('|'), "input_delimited('|')"),
+            (InputFormat::Split(' '), "input_split(' ')"),
+            (
+                InputFormat::FixedWidth(vec![10, 5]),
+                "input_fixed_width(&[10, 5])",
+            ),
         ];
+        for (format, helper) in cases {
+            let input = InputSource::new(Vec::new(), format.clone());
+            let code = CodeGenerator::new("_".to_string(), input, OutputFormat::Debug, false)
+                .generate()
+                .unwrap()
+                .0;
+            assert!(
+                code.contains(&format!("let stdin_data = {helper};")),
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_header_selects_headerless_helpers() {
+        let generate_input = |format, files: Vec<std::path::PathBuf>, header| {
+            let input = InputSource::new(files, format).with_header(header);
+            CodeGenerator::new("_".to_string(), input, OutputFormat::Debug, false)
+                .generate()
+                .unwrap()
+                .0
+        };
+        assert!(generate_input(InputFormat::Csv, vec![], true).contains("= input_csv();"));
+        assert!(
+            generate_input(InputFormat::Csv, vec![], false).contains("= input_csv_headerless();")
+        );
+        assert!(
+            generate_input(InputFormat::Tsv, vec!["a.tsv".into()], false)
+                .contains("= input_tsv_headerless_from_files(&files);")
+        );
+        // Formats without headers are unaffected
+        assert!(generate_input(InputFormat::Lines, vec![], false).contains("= input();"));
+    }
+
+    #[test]
+    fn stats_wraps_input_with_counter() {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        let code = CodeGenerator::new("_.count()".to_string(), input, OutputFormat::Debug, true)
+            .with_stats_interval_ms(250)
+            .generate()
+            .unwrap()
+            .0;
+        assert!(code.contains("let item_count = Arc::new(AtomicUsize::new(0));"));
+        assert!(code.contains("let print_interval_ms: u64 = 250;"));
+        assert!(code.contains("now_ms - last.load(Ordering::Relaxed) >= print_interval_ms"));
+        assert!(!code.contains("count - last_val"));
+        assert!(code.contains("[Stats] Items:"));
+        assert!(code.contains("[Final Stats]"));
+    }
+
+    #[test]
+    fn no_stats_code_by_default() {
+        let code = generate("_.count()", OutputFormat::Debug);
+        assert!(!code.contains("AtomicUsize"));
+        assert!(!code.contains("[Stats]"));
+    }
+
+    #[test]
+    fn file_input_passes_format_options() {
+        let input = InputSource::new(vec!["a.txt".into()], InputFormat::Delimited(';'));
+        let code = CodeGenerator::new("_".to_string(), input, OutputFormat::Debug, false)
+            .generate()
+            .unwrap()
+            .0;
+        assert!(code.contains("let stdin_data = input_delimited_from_files(&files, ';');"));
+    }
+
+    fn is_terminal(expression: &str) -> bool {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        CodeGenerator::new(expression.to_string(), input, OutputFormat::Debug, false)
+            .has_terminal_operation()
+    }
+
+    #[test]
+    fn terminal_detected_at_end_of_chain() {
+        assert!(is_terminal("_.filter(|l| !l.is_empty()).count()"));
+        assert!(is_terminal("_.map(|l| l.len()).sum::<usize>()"));
+        assert!(is_terminal("_.to_list().len()"));
+    }
+
+    #[test]
+    fn terminal_name_in_string_literal_is_ignored() {
+        assert!(!is_terminal(r#"_.filter(|l| l.contains(".count()"))"#));
+        assert!(!is_terminal(r#"_.map(|l| format!("{} .sum()", l))"#));
+    }
+
+    #[test]
+    fn terminal_name_in_closure_is_ignored() {
+        assert!(!is_terminal("_.map(|l| l.split(',').count())"));
+        assert!(!is_terminal(
+            "_.filter(|l| l.chars().all(char::is_numeric))"
+        ));
+    }
+
+    #[test]
+    fn terminal_name_in_comment_is_ignored() {
+        assert!(!is_terminal("_.take(3) /* .count() */"));
+    }
+
+    #[test]
+    fn single_placeholder_streams_stdin() {
+        let code = generate("_.take(3)", OutputFormat::Debug);
+        assert!(code.contains("let result = stdin_data.take(3);"));
+        assert!(!code.contains("stdin_data.collect()"));
+    }
+
+    #[test]
+    fn multiple_placeholders_self_join() {
+        let code = generate(
+            "_.join_inner(_, |a| a.len(), |b| b.len())",
+            OutputFormat::Debug,
+        );
+        assert!(code.contains("let stdin_data: Vec<_> = stdin_data.collect();"));
+        assert!(code.contains(
+            "let result = lob(stdin_data.clone()).join_inner(lob(stdin_data.clone()), |a| a.len(), |b| b.len());"
+        ));
+    }
+
+    #[test]
+    fn underscore_identifiers_are_left_alone() {
+        let code = generate(
+            "_.map(|my_var| my_var.len() + _private())",
+            OutputFormat::Debug,
+        );
+        assert!(code.contains("let result = stdin_data.map(|my_var| my_var.len() + _private());"));
+    }
+
+    #[test]
+    fn wildcard_patterns_and_types_are_left_alone() {
+        let expr = "_.enumerate().map(|(_, v)| v).collect::<Vec<_>>()";
+        let code = generate(expr, OutputFormat::Debug);
+        assert!(code
+            .contains("let result = stdin_data.enumerate().map(|(_, v)| v).collect::<Vec<_>>();"));
+    }
+
+    #[test]
+    fn no_placeholder_skips_input() {
+        let code = generate("range(0, 3)", OutputFormat::Debug);
+        assert!(!code.contains("stdin_data"));
+    }
+
+    #[test]
+    fn single_expression_is_not_wrapped() {
+        let code = generate("_.take(2)", OutputFormat::Debug);
+        assert!(code.contains("    let result = stdin_data.take(2);\n"));
+    }
+
+    #[test]
+    fn statements_become_a_block() {
+        let code = generate("let n = 5; _.take(n)", OutputFormat::Debug);
+        assert!(
+            code.contains("    let result = {\n        let n = 5; stdin_data.take(n)\n    };\n")
+        );
+        assert!(code.contains("for item in result {"));
+    }
+
+    #[test]
+    fn terminal_detected_in_final_expression() {
+        assert!(is_terminal(
+            "let f = |l: &String| l.len(); _.map(f).sum::<usize>()"
+        ));
+        assert!(!is_terminal("let total = 1; _.take(total);"));
+        assert!(!is_terminal("let n = _.count(); range(0, n as i64)"));
+    }
+
+    #[test]
+    fn semicolons_inside_closures_are_not_statements() {
+        assert_eq!(
+            final_expression("_.map(|x| { let y = x; y })"),
+            "_.map(|x| { let y = x; y })"
+        );
+        assert_eq!(final_expression("let a = 1;\n_.take(a)"), "_.take(a)");
+    }
+
+    #[test]
+    fn table_output_imports_tabled() {
+        let code = generate("_", OutputFormat::Table);
+        assert!(code.contains("use lob_prelude::tabled::builder::Builder;"));
+    }
+
+    #[test]
+    fn span_points_at_expression_in_source() {
+        let (code, span) = span("range(0, 3).sum::<i32>()");
+        let line = code.lines().nth(span.line - 1).unwrap();
+        assert_eq!(
+            &line[span.column - 1..],
+            "range(0, 3).sum::<i32>();".to_string()
+        );
+        assert_eq!(span.original_offset(span.line, span.column + 12), Some(12));
+        assert_eq!(span.original_offset(span.line + 1, span.column), None);
+    }
+
+    #[test]
+    fn span_maps_through_placeholder_substitution() {
+        let expr = "_.mapp(|x| x)";
+        let (_, span) = span(expr);
+        // `stdin_data.mapp(...)`: "mapp" starts 11 chars into the generated text
+        let mapped = span.original_offset(span.line, span.column + 11).unwrap();
+        assert!(expr[mapped..].starts_with("mapp"));
+        // Anywhere inside `stdin_data` points at the `_`
+        assert_eq!(span.original_offset(span.line, span.column + 4), Some(0));
+    }
+
+    #[test]
+    fn span_maps_statements_block() {
+        let expr = "let n = 2; _.take(n).bogus()";
+        let (code, span) = span(expr);
+        let line = code.lines().nth(span.line - 1).unwrap();
+        let col = line.find("bogus").unwrap() + 1;
+        let mapped = span.original_offset(span.line, col).unwrap();
+        assert!(expr[mapped..].starts_with("bogus"));
+    }
+
+    #[test]
+    fn explain_terminal_expression() {
+        let input = InputSource::new(Vec::new(), InputFormat::Csv);
+        let generator =
+            CodeGenerator::new("_.count()".to_string(), input, OutputFormat::Json, false);
+        let text = generator.explain("abc123");
+        assert!(text.contains("terminal operation: yes"));
+        assert!(text.contains("input format: csv"));
+        assert!(text.contains("reads input: yes (stdin)"));
+        assert!(text.contains("output format: json"));
+        assert!(text.contains("cache hash: abc123"));
+    }
+
+    #[test]
+    fn explain_iterator_without_input() {
+        let input = InputSource::new(vec!["data.txt".into()], InputFormat::Lines);
+        let generator = CodeGenerator::new(
+            "range(0, 5).map(|x| x * 2)".to_string(),
+            input,
+            OutputFormat::Debug,
+            false,
+        );
+        let text = generator.explain("h");
+        assert!(text.contains("terminal operation: no"));
+        assert!(text.contains("reads input: no"));
+        assert!(text.contains("output: one item per element"));
+    }
+
+    #[test]
+    fn explain_lists_input_files_and_buffering() {
+        let input = InputSource::new(vec!["a.csv".into()], InputFormat::Csv);
+        let generator = CodeGenerator::new(
+            "_.join_inner(_, |l| l[\"id\"].clone(), |r| r[\"id\"].clone())".to_string(),
+            input,
+            OutputFormat::JsonLines,
+            false,
+        );
+        let text = generator.explain("h");
+        assert!(text.contains("reads input: yes (a.csv)"));
+        assert!(text.contains("input placeholders: 2"));
+    }
+
+    #[test]
+    fn rejects_reserved_identifier() {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        let err = CodeGenerator::new(
+            "_.map(|stdin_data| stdin_data.len())".to_string(),
+            input,
+            OutputFormat::Debug,
+            false,
+        )
+        .generate()
+        .unwrap_err();
+        assert!(matches!(err, LobError::InvalidExpression(_)));
+        assert!(err.to_string().contains("`stdin_data`"));
+    }
+
+    #[test]
+    fn reserved_words_in_strings_and_fields_are_allowed() {
+        let code = generate(
+            r#"_.filter(|l| l.contains("result")).map(|l| l.len())"#,
+            OutputFormat::Debug,
+        );
+        assert!(code.contains(r#"l.contains("result")"#));
+        let code = generate("_.map(|r| r.result)", OutputFormat::Debug);
+        assert!(code.contains("r.result"));
+    }
+
+    #[test]
+    fn count_only_skips_serialization() {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        let code = CodeGenerator::new(
+            "_.filter(|l| !l.is_empty())".to_string(),
+            input,
+            OutputFormat::Json,
+            false,
+        )
+        .with_count_only(true)
+        .generate()
+        .unwrap()
+        .0;
+        assert!(code.ends_with("    println!(\"{}\", result.count());\n}\n"));
+        assert!(!code.contains("serde_json"));
+        assert!(!code.contains("result.collect()"));
+    }
+
+    #[test]
+    fn limit_wraps_iterator_result() {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        let generator = CodeGenerator::new(
+            "_.map(|l| l.len())".to_string(),
+            input,
+            OutputFormat::Debug,
+            false,
+        )
+        .with_limit(Some(10));
+        assert!(!generator.limit_ignored());
+        let code = generator.generate().unwrap().0;
+        assert!(code.contains("    let result = result.take(10);\n    for item in result {"));
+    }
 
-        terminals.iter().any(|t| self.expression.contains(t))
+    #[test]
+    fn limit_ignored_for_terminal_result() {
+        let input = InputSource::new(Vec::new(), InputFormat::Lines);
+        let generator =
+            CodeGenerator::new("_.count()".to_string(), input, OutputFormat::Debug, false)
+                .with_limit(Some(10));
+        assert!(generator.limit_ignored());
+        assert!(!generator.generate().unwrap().0.contains(".take(10)"));
     }
 }
@@ -1,16 +1,26 @@
 //! Compilation of generated Rust code
 
 use crate::cache::Cache;
+use crate::codegen::ExpressionSpan;
+use crate::diagnostics::{ErrorFormat, ErrorReport};
 use crate::error::{LobError, Result};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Optimization level used unless `--opt` says otherwise
+pub const DEFAULT_OPT_LEVEL: u8 = 3;
+
+/// Rust edition generated programs are compiled with
+const EDITION: &str = "2021";
+
 /// Result of compilation with cache information
 pub struct CompileResult {
     /// Path to the compiled binary
     pub binary_path: PathBuf,
     /// Whether the binary was found in cache
     pub cache_hit: bool,
+    /// Cache key the binary is stored under
+    pub hash: String,
 }
 
 /// Resolved paths to lob rlib files needed for compilation
@@ -26,6 +36,12 @@ pub struct Compiler {
     rustc_path: PathBuf,
     /// Path to sysroot (for embedded toolchain)
     sysroot: Option<PathBuf>,
+    /// `rustc --version` output, part of every cache key
+    version: String,
+    /// `-C opt-level` passed to rustc (0-3)
+    opt_level: u8,
+    /// How compilation errors are reported
+    error_format: ErrorFormat,
 }
 
 /// Find a file matching `{prefix}*.rlib` in a directory
@@ -45,6 +61,44 @@ fn find_rlib_in_dir(dir: &Path, prefix: &str) -> Option<PathBuf> {
     })
 }
 
+/// Run `rustc --version`, returning its trimmed output
+fn rustc_version(rustc_path: &Path) -> Option<String> {
+    let output = Command::new(rustc_path).arg("--version").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Starting points for the rlib search, normally taken from the process
+struct SearchRoots {
+    /// `CARGO_MANIFEST_DIR`, set when run through cargo
+    manifest_dir: Option<PathBuf>,
+    /// Path of the running executable
+    exe_path: Option<PathBuf>,
+    /// Current working directory
+    cwd: Option<PathBuf>,
+}
+
+impl SearchRoots {
+    fn from_env() -> Self {
+        Self {
+            manifest_dir: std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from),
+            exe_path: std::env::current_exe().ok(),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+}
+
+/// Extract `target_directory` from `cargo metadata` JSON output
+fn metadata_target_dir(metadata: &[u8]) -> Option<PathBuf> {
+    let metadata: serde_json::Value = serde_json::from_slice(metadata).ok()?;
+    metadata
+        .get("target_directory")?
+        .as_str()
+        .map(PathBuf::from)
+}
+
 impl Compiler {
     /// Try to locate lob rlib files in a build directory (debug or release).
     ///
@@ -79,11 +133,24 @@ impl Compiler {
         None
     }
 
+    /// Locate the prelude rlibs, or explain how to build them
+    ///
+    /// `target_dir` is only consulted (it may run `cargo`) when every
+    /// directory derived from `roots` misses.
+    fn locate_rlibs<F>(roots: &SearchRoots, target_dir: F) -> Result<RlibPaths>
+    where
+        F: FnOnce() -> Option<PathBuf>,
+    {
+        Self::find_rlib_paths(roots, target_dir).ok_or_else(Self::missing_prelude_error)
+    }
+
     /// Find the rlib paths for `lob_prelude` and `lob_core` across multiple strategies
-    fn find_rlib_paths() -> Option<RlibPaths> {
+    fn find_rlib_paths<F>(roots: &SearchRoots, target_dir: F) -> Option<RlibPaths>
+    where
+        F: FnOnce() -> Option<PathBuf>,
+    {
         // Strategy 1: Use CARGO_MANIFEST_DIR (works during cargo test/run)
-        if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
-            let root = PathBuf::from(manifest_dir);
+        if let Some(root) = &roots.manifest_dir {
             for ancestor in root.ancestors() {
                 let target = ancestor.join("target");
                 if let Some(rlibs) = Self::find_rlibs_in(&target.join("debug")) {
@@ -96,34 +163,32 @@ impl Compiler {
         }
 
         // Strategy 2: Look relative to the executable
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                // Test executables live in deps/
-                if exe_dir.ends_with("deps") {
-                    if let Some(build_dir) = exe_dir.parent() {
-                        if let Some(rlibs) = Self::find_rlibs_in(build_dir) {
-                            return Some(rlibs);
-                        }
-                    }
-                }
-
-                if let Some(target_parent) = exe_dir.parent() {
-                    if let Some(rlibs) = Self::find_rlibs_in(&target_parent.join("debug")) {
-                        return Some(rlibs);
-                    }
-                    if let Some(rlibs) = Self::find_rlibs_in(&target_parent.join("release")) {
+        if let Some(exe_dir) = roots.exe_path.as_deref().and_then(Path::parent) {
+            // Test executables live in deps/
+            if exe_dir.ends_with("deps") {
+                if let Some(build_dir) = exe_dir.parent() {
+                    if let Some(rlibs) = Self::find_rlibs_in(build_dir) {
                         return Some(rlibs);
                     }
                 }
+            }
 
-                if let Some(rlibs) = Self::find_rlibs_in(exe_dir) {
+            if let Some(target_parent) = exe_dir.parent() {
+                if let Some(rlibs) = Self::find_rlibs_in(&target_parent.join("debug")) {
+                    return Some(rlibs);
+                }
+                if let Some(rlibs) = Self::find_rlibs_in(&target_parent.join("release")) {
                     return Some(rlibs);
                 }
             }
+
+            if let Some(rlibs) = Self::find_rlibs_in(exe_dir) {
+                return Some(rlibs);
+            }
         }
 
         // Strategy 3: Look in current working directory
-        if let Ok(cwd) = std::env::current_dir() {
+        if let Some(cwd) = &roots.cwd {
             if let Some(rlibs) = Self::find_rlibs_in(&cwd.join("target").join("debug")) {
                 return Some(rlibs);
             }
@@ -133,24 +198,53 @@ impl Compiler {
         }
 
         // Strategy 4: Walk up from cwd
-        if let Ok(mut current) = std::env::current_dir() {
-            loop {
-                let target = current.join("target");
+        if let Some(cwd) = &roots.cwd {
+            for ancestor in cwd.ancestors() {
+                let target = ancestor.join("target");
                 if let Some(rlibs) = Self::find_rlibs_in(&target.join("debug")) {
                     return Some(rlibs);
                 }
                 if let Some(rlibs) = Self::find_rlibs_in(&target.join("release")) {
                     return Some(rlibs);
                 }
-                if !current.pop() {
-                    break;
-                }
+            }
+        }
+
+        // Strategy 5: Ask cargo where the workspace target directory is
+        if let Some(target) = target_dir() {
+            if let Some(rlibs) = Self::find_rlibs_in(&target.join("debug")) {
+                return Some(rlibs);
+            }
+            if let Some(rlibs) = Self::find_rlibs_in(&target.join("release")) {
+                return Some(rlibs);
             }
         }
 
         None
     }
 
+    /// Query `cargo metadata` for the target directory of the enclosing workspace
+    fn cargo_target_dir() -> Option<PathBuf> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        metadata_target_dir(&output.stdout)
+    }
+
+    /// Error returned when the prelude rlibs cannot be located
+    fn missing_prelude_error() -> LobError {
+        LobError::Toolchain(
+            "could not find the lob_prelude/lob_core libraries needed to compile expressions.\n  \
+             Build them from the lob source tree with `cargo build -p lob-prelude` \
+             (or `cargo build --release -p lob-prelude`) and run lob from that directory."
+                .to_string(),
+        )
+    }
+
     /// Create a new compiler using system rustc
     pub fn system() -> Result<Self> {
         // Check if rustc is available
@@ -172,23 +266,59 @@ impl Compiler {
         Ok(Self {
             rustc_path: PathBuf::from("rustc"),
             sysroot: None,
+            version: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            opt_level: DEFAULT_OPT_LEVEL,
+            error_format: ErrorFormat::Human,
         })
     }
 
     /// Create a compiler with custom rustc path and sysroot
     pub fn custom(rustc_path: PathBuf, sysroot: Option<PathBuf>) -> Self {
         Self {
+            version: rustc_version(&rustc_path).unwrap_or_default(),
             rustc_path,
             sysroot,
+            opt_level: DEFAULT_OPT_LEVEL,
+            error_format: ErrorFormat::Human,
         }
     }
 
+    /// Set the optimization level (0-3)
+    ///
+    /// Lower levels compile faster but the resulting binary runs slower.
+    #[must_use]
+    pub fn with_opt_level(mut self, opt_level: u8) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    /// Set how compilation errors are reported
+    ///
+    /// With [`ErrorFormat::Json`], rustc's own JSON diagnostics are parsed and
+    /// the error message is a single JSON object.
+    #[must_use]
+    pub fn with_error_format(mut self, error_format: ErrorFormat) -> Self {
+        self.error_format = error_format;
+        self
+    }
+
+    /// Compiler version and fixed flags, as fed into the cache key
+    pub fn toolchain_id(&self) -> String {
+        format!("{} --edition={EDITION}", self.version)
+    }
+
+    /// Cache key for a generated program built by this compiler
+    pub fn cache_key(&self, cache: &Cache, source: &str) -> String {
+        cache.hash_source(source, &self.toolchain_id(), self.opt_level)
+    }
+
     /// Compile source code to binary
     pub fn compile(
         &self,
         source_path: &Path,
         output_path: &Path,
         user_expr: Option<&str>,
+        span: Option<&ExpressionSpan>,
     ) -> Result<()> {
         // Compile to a temp directory so intermediate .rcgu.o files don't land
         // in the cache (where concurrent cache-clear could delete them).
@@ -205,25 +335,29 @@ impl Compiler {
 
         let mut cmd = Command::new(&self.rustc_path);
 
-        cmd.arg("--edition=2021")
+        cmd.arg(format!("--edition={EDITION}"))
             .arg("-C")
-            .arg("opt-level=3")
+            .arg(format!("opt-level={}", self.opt_level))
             .arg("--crate-type")
             .arg("bin")
             .arg("-o")
             .arg(&temp_output)
             .arg(source_path);
 
-        // Add extern crate paths for lob-prelude and its dependencies
-        if let Some(rlibs) = Self::find_rlib_paths() {
-            cmd.arg("--extern")
-                .arg(format!("lob_prelude={}", rlibs.lob_prelude.display()))
-                .arg("--extern")
-                .arg(format!("lob_core={}", rlibs.lob_core.display()))
-                .arg("-L")
-                .arg(format!("dependency={}", rlibs.deps_dir.display()));
+        if self.error_format == ErrorFormat::Json {
+            cmd.arg("--error-format=json");
         }
 
+        // Add extern crate paths for lob-prelude and its dependencies. Without
+        // them rustc would fail with an unhelpful "can't find crate" error.
+        let rlibs = Self::locate_rlibs(&SearchRoots::from_env(), Self::cargo_target_dir)?;
+        cmd.arg("--extern")
+            .arg(format!("lob_prelude={}", rlibs.lob_prelude.display()))
+            .arg("--extern")
+            .arg(format!("lob_core={}", rlibs.lob_core.display()))
+            .arg("-L")
+            .arg(format!("dependency={}", rlibs.deps_dir.display()));
+
         // Add sysroot if provided (for embedded toolchain)
         if let Some(sysroot) = &self.sysroot {
             cmd.arg("--sysroot").arg(sysroot);
@@ -234,7 +368,10 @@ impl Compiler {
         if !output.status.success() {
             let _ = std::fs::remove_dir_all(&temp_dir);
             let stderr = String::from_utf8_lossy(&output.stderr);
-            let formatted = LobError::format_compilation_error(&stderr, user_expr);
+            let formatted = match self.error_format {
+                ErrorFormat::Human => LobError::format_compilation_error(&stderr, user_expr, span),
+                ErrorFormat::Json => ErrorReport::from_rustc_json(&stderr, user_expr).to_json(),
+            };
             return Err(LobError::Compilation(formatted));
         }
 
@@ -252,14 +389,18 @@ impl Compiler {
         source: &str,
         cache: &Cache,
         user_expr: Option<&str>,
+        span: Option<&ExpressionSpan>,
     ) -> Result<CompileResult> {
-        let hash = cache.hash_source(source);
+        let hash = self.cache_key(cache, source);
 
         // Check cache first
         if let Some(binary_path) = cache.get_binary(&hash) {
+            // Keep frequently used binaries at the back of the eviction queue
+            let _ = cache.touch(&hash);
             return Ok(CompileResult {
                 binary_path,
                 cache_hit: true,
+                hash,
             });
         }
 
@@ -267,11 +408,115 @@ impl Compiler {
         let source_path = cache.store_source(&hash, source)?;
         let binary_path = cache.binary_path(&hash);
 
-        self.compile(&source_path, &binary_path, user_expr)?;
+        self.compile(&source_path, &binary_path, user_expr, span)?;
 
         Ok(CompileResult {
             binary_path,
             cache_hit: false,
+            hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_rlibs_in_empty_dir_is_none() {
+        let dir = std::env::temp_dir().join(format!("lob-rlib-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("deps")).unwrap();
+        assert!(Compiler::find_rlibs_in(&dir).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn find_rlibs_in_hashed_deps() {
+        let dir = std::env::temp_dir().join(format!("lob-rlib-hashed-{}", std::process::id()));
+        let deps = dir.join("deps");
+        std::fs::create_dir_all(&deps).unwrap();
+        std::fs::write(deps.join("liblob_prelude-abc.rlib"), b"").unwrap();
+        std::fs::write(deps.join("liblob_core-def.rlib"), b"").unwrap();
+
+        let rlibs = Compiler::find_rlibs_in(&dir).unwrap();
+        assert_eq!(rlibs.lob_prelude, deps.join("liblob_prelude-abc.rlib"));
+        assert_eq!(rlibs.lob_core, deps.join("liblob_core-def.rlib"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Roots that find nothing, so only the metadata fallback can succeed
+    fn empty_roots(dir: &Path) -> SearchRoots {
+        SearchRoots {
+            manifest_dir: None,
+            exe_path: None,
+            cwd: Some(dir.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn locate_rlibs_falls_back_to_metadata_target_dir() {
+        let dir = std::env::temp_dir().join(format!("lob-rlib-metadata-{}", std::process::id()));
+        let cwd = dir.join("elsewhere");
+        let deps = dir.join("shared-target").join("release").join("deps");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::create_dir_all(&deps).unwrap();
+        std::fs::write(deps.join("liblob_prelude-abc.rlib"), b"").unwrap();
+        std::fs::write(deps.join("liblob_core-def.rlib"), b"").unwrap();
+        let metadata = serde_json::json!({
+            "packages": [],
+            "target_directory": dir.join("shared-target"),
+        })
+        .to_string();
+
+        let rlibs = Compiler::locate_rlibs(&empty_roots(&cwd), || {
+            metadata_target_dir(metadata.as_bytes())
         })
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(rlibs.lob_prelude, deps.join("liblob_prelude-abc.rlib"));
+        assert_eq!(rlibs.lob_core, deps.join("liblob_core-def.rlib"));
+    }
+
+    #[test]
+    fn locate_rlibs_reports_missing_prelude() {
+        let dir = std::env::temp_dir().join(format!("lob-rlib-missing-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let metadata = serde_json::json!({ "target_directory": dir.join("target") }).to_string();
+
+        let err = Compiler::locate_rlibs(&empty_roots(&dir), || {
+            metadata_target_dir(metadata.as_bytes())
+        })
+        .err()
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(matches!(err, LobError::Toolchain(_)));
+        let msg = err.to_string();
+        assert!(msg.contains("lob_prelude"));
+        assert!(msg.contains("cargo build -p lob-prelude"));
+    }
+
+    #[test]
+    fn metadata_target_dir_requires_field() {
+        assert_eq!(
+            metadata_target_dir(br#"{"target_directory":"/ws/target"}"#),
+            Some(PathBuf::from("/ws/target"))
+        );
+        assert_eq!(metadata_target_dir(br#"{"packages":[]}"#), None);
+        assert_eq!(metadata_target_dir(b"not json"), None);
+    }
+
+    #[test]
+    fn toolchain_id_includes_version_and_edition() {
+        let compiler = |version: &str| Compiler {
+            version: version.to_string(),
+            ..Compiler::custom(PathBuf::from("/nonexistent/rustc"), None)
+        };
+        let old = compiler("rustc 1.80.0 (051478957 2024-07-21)");
+        let new = compiler("rustc 1.81.0 (eeb90cda1 2024-09-04)");
+        assert!(old.toolchain_id().contains("1.80.0"));
+        assert!(old.toolchain_id().contains("--edition=2021"));
+        assert_ne!(old.toolchain_id(), new.toolchain_id());
     }
 }
@@ -0,0 +1,185 @@
+//! Machine-readable compilation errors (`--error-format json`)
+
+use serde::{Deserialize, Serialize};
+
+/// How compilation errors are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// Colored, human-readable output with suggestions
+    #[default]
+    Human,
+    /// A single JSON object for editors and scripts
+    Json,
+}
+
+impl ErrorFormat {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "human" => Some(Self::Human),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// One line of `rustc --error-format=json` output
+#[derive(Debug, Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    level: String,
+    code: Option<RustcCode>,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+}
+
+/// Error code attached to a rustc diagnostic
+#[derive(Debug, Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+/// Source span of a rustc diagnostic
+#[derive(Debug, Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// A compiler diagnostic pointing at the generated source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// File name (without the cache directory)
+    pub file: String,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column
+    pub col: usize,
+    /// `error`, `warning`, ...
+    pub level: String,
+    /// Diagnostic message
+    pub message: String,
+    /// Error code such as `E0599`, if any
+    pub code: Option<String>,
+}
+
+/// JSON error report printed on compilation failure
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    /// Error category
+    pub kind: &'static str,
+    /// Summary message (the first error)
+    pub message: String,
+    /// The expression as the user typed it
+    pub user_expression: Option<String>,
+    /// All located diagnostics, in rustc order
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ErrorReport {
+    /// Build a report from `rustc --error-format=json` stderr
+    pub fn from_rustc_json(stderr: &str, user_expression: Option<&str>) -> Self {
+        let diagnostics = parse_rustc_json(stderr);
+        let message = diagnostics
+            .iter()
+            .find(|d| d.level == "error")
+            .map_or_else(|| "compilation failed".to_string(), |d| d.message.clone());
+        Self {
+            kind: "compilation",
+            message,
+            user_expression: user_expression.map(str::to_string),
+            diagnostics,
+        }
+    }
+
+    /// Serialize the report as a single line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Parse the diagnostics that have a primary source location
+///
+/// Lines that are not JSON diagnostics (e.g. linker noise) are skipped, as
+/// are location-less summaries such as "aborting due to 1 previous error".
+pub fn parse_rustc_json(stderr: &str) -> Vec<Diagnostic> {
+    stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RustcDiagnostic>(line).ok())
+        .filter_map(|diag| {
+            let span = diag.spans.iter().find(|s| s.is_primary)?;
+            Some(Diagnostic {
+                file: span
+                    .file_name
+                    .rsplit(['/', '\\'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                line: span.line_start,
+                col: span.column_start,
+                level: diag.level,
+                message: diag.message,
+                code: diag.code.map(|c| c.code),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = concat!(
+        r#"{"$message_type":"diagnostic","message":"no method named `mapp` found for struct `Lob` in the current scope","code":{"code":"E0599","explanation":null},"level":"error","spans":[{"file_name":"/home/u/.cache/lob/abc123.rs","byte_start":120,"byte_end":124,"line_start":7,"line_end":7,"column_start":30,"column_end":34,"is_primary":true,"text":[],"label":"method not found","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"error[E0599]: ..."}"#,
+        "\n",
+        r#"{"$message_type":"diagnostic","message":"unused import: `std::collections::HashMap`","code":{"code":"unused_imports","explanation":null},"level":"warning","spans":[{"file_name":"abc123.rs","byte_start":24,"byte_end":49,"line_start":2,"line_end":2,"column_start":5,"column_end":30,"is_primary":true,"text":[],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"warning: ..."}"#,
+        "\n",
+        r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}"#,
+        "\n",
+    );
+
+    #[test]
+    fn parses_primary_spans() {
+        let diags = parse_rustc_json(SAMPLE);
+        assert_eq!(diags.len(), 2);
+        assert_eq!(
+            diags[0],
+            Diagnostic {
+                file: "abc123.rs".to_string(),
+                line: 7,
+                col: 30,
+                level: "error".to_string(),
+                message: "no method named `mapp` found for struct `Lob` in the current scope"
+                    .to_string(),
+                code: Some("E0599".to_string()),
+            }
+        );
+        assert_eq!(diags[1].level, "warning");
+    }
+
+    #[test]
+    fn skips_non_json_lines() {
+        let stderr = format!("note: linker noise\n{SAMPLE}");
+        assert_eq!(parse_rustc_json(&stderr).len(), 2);
+    }
+
+    #[test]
+    fn report_uses_first_error_as_message() {
+        let report = ErrorReport::from_rustc_json(SAMPLE, Some("_.mapp(|x| x)"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["kind"], "compilation");
+        assert!(json["message"].as_str().unwrap().contains("mapp"));
+        assert_eq!(json["user_expression"], "_.mapp(|x| x)");
+        assert_eq!(json["diagnostics"][0]["line"], 7);
+        assert_eq!(json["diagnostics"][0]["col"], 30);
+    }
+
+    #[test]
+    fn report_without_errors_has_generic_message() {
+        let report = ErrorReport::from_rustc_json("", None);
+        assert_eq!(report.message, "compilation failed");
+        assert!(report.diagnostics.is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 //! Error types for lob CLI
 
+use crate::codegen::ExpressionSpan;
 use crate::suggestion;
+use crate::tokenizer::tokenize;
 use colored::Colorize;
 use thiserror::Error;
 
@@ -26,6 +28,14 @@ pub enum LobError {
     /// Invalid expression
     #[error("Invalid expression: {0}")]
     InvalidExpression(String),
+
+    /// Snippet store error
+    #[error("Snippet error: {0}")]
+    Snippet(String),
+
+    /// Execution exceeded the `--timeout` deadline
+    #[error("Execution timed out after {0:?}")]
+    Timeout(std::time::Duration),
 }
 
 /// Result type for lob operations
@@ -33,7 +43,14 @@ pub type Result<T> = std::result::Result<T, LobError>;
 
 impl LobError {
     /// Format a compilation error with colors and context
-    pub fn format_compilation_error(stderr: &str, user_expression: Option<&str>) -> String {
+    ///
+    /// When `span` is given, the first rustc location inside the user
+    /// expression is underlined beneath it.
+    pub fn format_compilation_error(
+        stderr: &str,
+        user_expression: Option<&str>,
+        span: Option<&ExpressionSpan>,
+    ) -> String {
         let mut output = Vec::new();
 
         // Header
@@ -47,6 +64,9 @@ impl LobError {
                 "Your expression:".cyan().bold(),
                 expr.yellow()
             ));
+            if let Some(caret) = span.and_then(|span| Self::expression_caret(stderr, expr, span)) {
+                output.push(format!("  {}", caret.red().bold()));
+            }
             output.push(String::new());
         }
 
@@ -142,6 +162,45 @@ impl LobError {
         output.join("\n")
     }
 
+    /// Caret line underlining the token rustc first complains about
+    ///
+    /// Padded to sit under the expression in the `Your expression:` line.
+    fn expression_caret(stderr: &str, expression: &str, span: &ExpressionSpan) -> Option<String> {
+        // Only locations belonging to errors, not warnings
+        let mut in_error = false;
+        let start = stderr.lines().find_map(|line| {
+            if line.starts_with("error") {
+                in_error = true;
+            } else if line.starts_with("warning") {
+                in_error = false;
+            }
+            let (line, column) = Self::parse_error_location(line).filter(|_| in_error)?;
+            span.original_offset(line, column)
+        })?;
+
+        // Underline the whole token at that position
+        let mut offset = 0;
+        let width = tokenize(expression)
+            .into_iter()
+            .find_map(|t| {
+                offset += t.text.len();
+                (offset > start).then(|| expression[start..offset].chars().count())
+            })
+            .unwrap_or(1);
+
+        let indent = "Your expression: ".len() + expression[..start].chars().count();
+        Some(format!("{}{}", " ".repeat(indent), "^".repeat(width)))
+    }
+
+    /// Line and column from a `--> path:line:col` location line
+    fn parse_error_location(line: &str) -> Option<(usize, usize)> {
+        let rest = line.trim_start().strip_prefix("-->")?.trim();
+        let mut parts = rest.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        Some((line, column))
+    }
+
     /// Simplify error location by removing cache path
     fn simplify_error_location(line: &str) -> Option<String> {
         // Try to extract just the filename from the full path
@@ -168,7 +227,7 @@ mod tests {
     #[test]
     fn format_error_with_user_expression() {
         let stderr = "error: expected `;`";
-        let formatted = LobError::format_compilation_error(stderr, Some("_.map(|x| x"));
+        let formatted = LobError::format_compilation_error(stderr, Some("_.map(|x| x"), None);
         assert!(formatted.contains("Your expression:"));
         assert!(formatted.contains("error: expected `;`"));
     }
@@ -176,7 +235,7 @@ mod tests {
     #[test]
     fn format_error_without_user_expression() {
         let stderr = "error: something went wrong";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(!formatted.contains("Your expression:"));
         assert!(formatted.contains("error: something went wrong"));
     }
@@ -184,14 +243,14 @@ mod tests {
     #[test]
     fn format_error_warning_header() {
         let stderr = "warning: unused variable";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("warning: unused variable"));
     }
 
     #[test]
     fn format_error_location_simplified() {
         let stderr = "  --> /path/to/file.rs:10:5";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("file.rs:10:5"));
         assert!(!formatted.contains("/path/to/"));
     }
@@ -199,14 +258,14 @@ mod tests {
     #[test]
     fn format_error_location_fallback() {
         let stderr = "  --> invalid-path-format";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("invalid-path-format"));
     }
 
     #[test]
     fn format_error_code_and_caret_lines() {
         let stderr = "error: test\n 1 | let x = y;\n     ^^^^^^";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("let x = y;"));
         assert!(formatted.contains("^^^^^^"));
     }
@@ -214,7 +273,7 @@ mod tests {
     #[test]
     fn format_error_help_and_note() {
         let stderr = "  = help: try this\n  = note: some context";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("= help: try this"));
         assert!(formatted.contains("= note: some context"));
     }
@@ -222,7 +281,93 @@ mod tests {
     #[test]
     fn format_error_summary_lines() {
         let stderr = "error: aborting due to 2 previous errors";
-        let formatted = LobError::format_compilation_error(stderr, None);
+        let formatted = LobError::format_compilation_error(stderr, None, None);
         assert!(formatted.contains("aborting due to"));
     }
+
+    fn caret_line(expression: &str, stderr_for: impl Fn(usize, usize) -> String) -> String {
+        let (_, span) = crate::codegen::CodeGenerator::new(
+            expression.to_string(),
+            crate::input::InputSource::new(vec![], crate::input::InputFormat::Lines),
+            crate::output::OutputFormat::Debug,
+            false,
+        )
+        .generate()
+        .unwrap();
+        let stderr = stderr_for(span.line, span.column);
+        colored::control::set_override(false);
+        let formatted = LobError::format_compilation_error(&stderr, Some(expression), Some(&span));
+        let lines: Vec<&str> = formatted.lines().collect();
+        let idx = lines
+            .iter()
+            .position(|l| l.contains("Your expression:"))
+            .unwrap();
+        lines[idx + 1].to_string()
+    }
+
+    /// Column of the caret run in a formatted caret line, relative to the expression
+    fn caret_target<'a>(expression: &'a str, caret: &str) -> &'a str {
+        let prefix = "  Your expression: ".len();
+        let start = caret.find('^').unwrap() - prefix;
+        let width = caret.matches('^').count();
+        &expression[start..start + width]
+    }
+
+    #[test]
+    fn caret_under_unknown_method() {
+        let expr = "_.mapp(|x| x)";
+        // rustc reports the method name; generated text is `stdin_data.mapp(...)`
+        let caret = caret_line(expr, |line, col| {
+            format!(
+                "error[E0599]: no method named `mapp` found\n --> /tmp/cache/abc.rs:{}:{}\n",
+                line,
+                col + 11
+            )
+        });
+        assert_eq!(caret_target(expr, &caret), "mapp");
+    }
+
+    #[test]
+    fn caret_without_placeholder() {
+        let expr = "range(0, 10).fliter(|x| x % 2 == 0)";
+        let caret = caret_line(expr, |line, col| {
+            format!(
+                "error[E0599]: no method\n  --> src/main.rs:{}:{}\n",
+                line,
+                col + 13
+            )
+        });
+        assert_eq!(caret_target(expr, &caret), "fliter");
+    }
+
+    #[test]
+    fn caret_skipped_for_locations_outside_expression() {
+        let expr = "_.count()";
+        let caret = caret_line(expr, |line, _| {
+            format!("error: boom\n --> main.rs:{}:1\n", line + 3)
+        });
+        assert!(!caret.contains('^'));
+    }
+
+    #[test]
+    fn caret_ignores_warning_locations() {
+        let expr = "_.map(|unused| 1).fliter(|x| true)";
+        let caret = caret_line(expr, |line, col| {
+            format!(
+                "warning: unused variable\n --> a.rs:{line}:{}\n\nerror[E0599]: no method\n --> a.rs:{line}:{}\n",
+                col + 16,
+                col + 27
+            )
+        });
+        assert_eq!(caret_target(expr, &caret), "fliter");
+    }
+
+    #[test]
+    fn parse_error_location_reads_line_and_column() {
+        assert_eq!(
+            LobError::parse_error_location("  --> /a/b/c.rs:12:34"),
+            Some((12, 34))
+        );
+        assert_eq!(LobError::parse_error_location("error: nope"), None);
+    }
 }
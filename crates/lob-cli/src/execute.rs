@@ -0,0 +1,57 @@
+//! Running compiled binaries
+
+use crate::error::{LobError, Result};
+use std::process::{Child, ExitStatus};
+use std::time::{Duration, Instant};
+
+/// How often a running child is polled while a timeout is active
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Wait for a child process, killing it if it outlives `timeout`
+pub fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> Result<ExitStatus> {
+    let Some(timeout) = timeout else {
+        return Ok(child.wait()?);
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            // The child may exit between the check and the kill
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(LobError::Timeout(timeout));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[cfg(unix)]
+    #[test]
+    fn sleeping_child_is_killed() {
+        let mut child = Command::new("sh").args(["-c", "sleep 5"]).spawn().unwrap();
+        let start = Instant::now();
+
+        let err = wait_with_timeout(&mut child, Some(Duration::from_millis(100))).unwrap_err();
+
+        assert!(matches!(err, LobError::Timeout(_)));
+        assert!(err.to_string().contains("timed out"));
+        assert!(start.elapsed() < Duration::from_secs(4));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fast_child_finishes_before_timeout() {
+        let mut child = Command::new("sh").args(["-c", "exit 0"]).spawn().unwrap();
+        let status = wait_with_timeout(&mut child, Some(Duration::from_secs(5))).unwrap();
+        assert!(status.success());
+    }
+}
@@ -1,10 +1,10 @@
 //! Input source handling (files and stdin)
 
 use crate::error::{LobError, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Input format for parsing
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InputFormat {
     /// Plain text lines
     Lines,
@@ -14,6 +14,56 @@ pub enum InputFormat {
     Tsv,
     /// JSON lines (one JSON object per line)
     JsonLines,
+    /// Fields split on a custom delimiter, keyed `col0`, `col1`, ...
+    Delimited(char),
+    /// Lines split on a character into `Vec<String>`, without CSV quoting
+    Split(char),
+    /// Fixed-width columns of the given character widths, keyed `col0`, ...
+    FixedWidth(Vec<usize>),
+}
+
+impl InputFormat {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "lines" => Some(Self::Lines),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "json" | "jsonl" | "jsonlines" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// Format implied by a file's extension (`.gz` is looked through)
+    ///
+    /// `.csv` is CSV, `.tsv` is TSV, `.json`/`.jsonl`/`.ndjson` are JSON lines
+    /// and anything else is plain lines.
+    pub fn from_extension(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let name = name.strip_suffix(".gz").unwrap_or(&name);
+        match Path::new(name).extension().and_then(|e| e.to_str()) {
+            Some("csv") => Self::Csv,
+            Some("tsv") => Self::Tsv,
+            Some("json" | "jsonl" | "ndjson") => Self::JsonLines,
+            _ => Self::Lines,
+        }
+    }
+
+    /// Name as accepted by `--input`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lines => "lines",
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::JsonLines => "jsonl",
+            Self::Delimited(_) => "delimited",
+            Self::Split(_) => "split",
+            Self::FixedWidth(_) => "fixed-width",
+        }
+    }
 }
 
 /// Input source configuration
@@ -23,12 +73,59 @@ pub struct InputSource {
     pub files: Vec<PathBuf>,
     /// Input format
     pub format: InputFormat,
+    /// Whether CSV/TSV input starts with a header row
+    pub header: bool,
 }
 
 impl InputSource {
     /// Create new input source from files
     pub fn new(files: Vec<PathBuf>, format: InputFormat) -> Self {
-        Self { files, format }
+        Self {
+            files,
+            format,
+            header: true,
+        }
+    }
+
+    /// Set whether CSV/TSV input has a header row
+    ///
+    /// Without one, every line is data and columns are keyed by position.
+    #[must_use]
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Detect the format from the files' extensions (`--input auto`)
+    ///
+    /// Stdin has no extension to go by, so it keeps `default`. All files must
+    /// share a format because rows of different formats have different types.
+    pub fn auto(files: Vec<PathBuf>, default: InputFormat) -> Result<Self> {
+        let formats = Self::detect_formats(&files);
+        let format = match formats.split_first() {
+            None => default,
+            Some((first, rest)) => {
+                if let Some(i) = rest.iter().position(|f| f != first) {
+                    return Err(LobError::InvalidExpression(format!(
+                        "--input auto: {} is {} but {} is {}; pass --input explicitly",
+                        files[0].display(),
+                        first.name(),
+                        files[i + 1].display(),
+                        rest[i].name()
+                    )));
+                }
+                first.clone()
+            }
+        };
+        Ok(Self::new(files, format))
+    }
+
+    /// Format of each file based on its extension
+    pub fn detect_formats(files: &[PathBuf]) -> Vec<InputFormat> {
+        files
+            .iter()
+            .map(|f| InputFormat::from_extension(f))
+            .collect()
     }
 
     /// Check if reading from stdin
@@ -49,3 +146,51 @@ impl InputSource {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_from_mixed_extensions() {
+        let files: Vec<PathBuf> = [
+            "a.csv", "b.TSV", "c.jsonl", "d.ndjson", "e.json", "f.log", "g", "h.csv.gz",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(
+            InputSource::detect_formats(&files),
+            vec![
+                InputFormat::Csv,
+                InputFormat::Tsv,
+                InputFormat::JsonLines,
+                InputFormat::JsonLines,
+                InputFormat::JsonLines,
+                InputFormat::Lines,
+                InputFormat::Lines,
+                InputFormat::Csv,
+            ]
+        );
+    }
+
+    #[test]
+    fn auto_uses_shared_format() {
+        let files = vec![PathBuf::from("a.csv"), PathBuf::from("b.csv.gz")];
+        let source = InputSource::auto(files, InputFormat::Lines).unwrap();
+        assert_eq!(source.format, InputFormat::Csv);
+    }
+
+    #[test]
+    fn auto_stdin_keeps_default() {
+        let source = InputSource::auto(Vec::new(), InputFormat::Tsv).unwrap();
+        assert_eq!(source.format, InputFormat::Tsv);
+    }
+
+    #[test]
+    fn auto_rejects_mixed_formats() {
+        let files = vec![PathBuf::from("a.csv"), PathBuf::from("b.jsonl")];
+        let err = InputSource::auto(files, InputFormat::Lines).unwrap_err();
+        assert!(err.to_string().contains("b.jsonl is jsonl"));
+    }
+}
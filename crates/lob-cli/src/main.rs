@@ -8,20 +8,26 @@
 mod cache;
 mod codegen;
 mod compile;
+mod diagnostics;
 mod error;
+mod execute;
 mod input;
 mod output;
+mod snippets;
 mod suggestion;
+mod tokenizer;
 mod toolchain;
 mod welcome;
 
 use cache::Cache;
 use clap::Parser;
-use codegen::CodeGenerator;
+use codegen::{CodeGenerator, ExpressionSpan};
 use compile::Compiler;
+use diagnostics::ErrorFormat;
 use error::{LobError, Result};
 use input::{InputFormat, InputSource};
-use output::OutputFormat;
+use output::{OutputFormat, TableStyle};
+use snippets::SnippetStore;
 use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::process::Command;
@@ -34,17 +40,46 @@ use toolchain::EmbeddedToolchain;
 #[command(version)]
 struct Args {
     /// Lob expression to execute
-    #[arg(value_name = "EXPRESSION", required_unless_present_any = ["show_source", "clear_cache", "cache_stats"])]
+    #[arg(value_name = "EXPRESSION", required_unless_present_any = ["show_source", "clear_cache", "cache_stats", "list_cache", "run", "list_snippets"])]
     expression: Option<String>,
 
     /// Input files (omit to read from stdin)
     #[arg(value_name = "FILE")]
     files: Vec<PathBuf>,
 
+    /// Read input from PATH (repeatable; read after any positional FILEs)
+    #[arg(long = "input-file", value_name = "PATH")]
+    input_files: Vec<PathBuf>,
+
+    /// Input format (equivalent to the --parse-* flags; auto picks it from file extensions)
+    #[arg(short = 'i', long, value_name = "FORMAT", conflicts_with_all = ["parse_csv", "parse_tsv", "parse_json"])]
+    #[arg(value_parser = ["lines", "csv", "tsv", "json", "jsonl", "auto"])]
+    input: Option<String>,
+
+    /// Split input lines on CHAR; columns are keyed col0, col1, ...
+    #[arg(long, value_name = "CHAR", conflicts_with_all = ["input", "parse_csv", "parse_tsv", "parse_json", "input_widths"])]
+    input_delim: Option<char>,
+
+    /// Split each input line on CHAR into a `Vec<String>` (no quoting rules)
+    #[arg(long, value_name = "CHAR", conflicts_with_all = ["input", "parse_csv", "parse_tsv", "parse_json", "input_widths", "input_delim"])]
+    split: Option<char>,
+
+    /// Parse input as fixed-width columns of these widths (e.g. 10,5,20)
+    #[arg(long, value_name = "WIDTHS", value_delimiter = ',', conflicts_with_all = ["input", "parse_csv", "parse_tsv", "parse_json"])]
+    input_widths: Vec<usize>,
+
     /// Parse input as CSV with headers (row is `HashMap<String, String>`)
     #[arg(long)]
     parse_csv: bool,
 
+    /// CSV/TSV input starts with a header row (the default)
+    #[arg(long, overrides_with = "no_header")]
+    header: bool,
+
+    /// CSV/TSV input has no header row; columns are keyed col0, col1, ...
+    #[arg(long, overrides_with = "header")]
+    no_header: bool,
+
     /// Parse input as TSV with headers
     #[arg(long)]
     parse_tsv: bool,
@@ -53,15 +88,45 @@ struct Args {
     #[arg(long)]
     parse_json: bool,
 
-    /// Output format
-    #[arg(short = 'f', long, value_name = "FORMAT")]
-    #[arg(value_parser = ["debug", "json", "jsonl", "csv", "table"])]
+    /// Output format (defaults to debug on a terminal, jsonl when piped)
+    #[arg(short = 'f', long, visible_alias = "output", value_name = "FORMAT")]
+    #[arg(value_parser = ["debug", "json", "jsonl", "csv", "table", "md", "yaml", "parquet"])]
     format: Option<String>,
 
+    /// Print only the number of output items, skipping serialization
+    #[arg(long, conflicts_with = "format")]
+    count: bool,
+
+    /// Stop after printing N items (ignored when the expression yields a single value)
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Border style for `--format table` (rounded, ascii, sharp, markdown, psql, modern, blank)
+    #[arg(long, value_name = "STYLE", default_value = "rounded")]
+    table_style: String,
+
+    /// Optimization level 0-3 (lower compiles faster but runs slower)
+    #[arg(long, value_name = "LEVEL", default_value_t = compile::DEFAULT_OPT_LEVEL)]
+    #[arg(value_parser = clap::value_parser!(u8).range(0..=3))]
+    opt: u8,
+
+    /// Kill the pipeline if it runs longer than this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
+    /// How to report compilation errors (json prints one object for tooling)
+    #[arg(long, value_name = "FORMAT", default_value = "human")]
+    #[arg(value_parser = ["human", "json"])]
+    error_format: String,
+
     /// Show generated source code without executing
     #[arg(short = 's', long)]
     show_source: bool,
 
+    /// Summarize how the expression would run (input, output, cache key) without compiling
+    #[arg(long, conflicts_with = "show_source")]
+    explain: bool,
+
     /// Clear the compilation cache
     #[arg(long)]
     clear_cache: bool,
@@ -70,6 +135,26 @@ struct Args {
     #[arg(long)]
     cache_stats: bool,
 
+    /// Save EXPRESSION as a named snippet instead of running it
+    #[arg(long, value_name = "NAME", conflicts_with = "run")]
+    save: Option<String>,
+
+    /// Run a saved snippet (positional arguments are then input files)
+    #[arg(long, value_name = "NAME")]
+    run: Option<String>,
+
+    /// List saved snippets
+    #[arg(long)]
+    list_snippets: bool,
+
+    /// List cached binaries with size and last use
+    #[arg(long)]
+    list_cache: bool,
+
+    /// Evict least-recently-used binaries once the cache exceeds SIZE (e.g. 500M, 2G)
+    #[arg(long, value_name = "SIZE", value_parser = cache::parse_size)]
+    cache_limit: Option<u64>,
+
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
@@ -77,6 +162,10 @@ struct Args {
     /// Show performance statistics after execution
     #[arg(long)]
     stats: bool,
+
+    /// With --stats, print progress at most once every MS milliseconds
+    #[arg(long, value_name = "MS", default_value_t = codegen::DEFAULT_STATS_INTERVAL_MS)]
+    stats_interval: u64,
 }
 
 fn main() {
@@ -91,7 +180,7 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Handle cache management commands
     if args.clear_cache {
@@ -111,6 +200,27 @@ fn run() -> Result<()> {
         return Ok(());
     }
 
+    if args.list_cache {
+        let cache = Cache::new()?;
+        let now = std::time::SystemTime::now();
+        for entry in cache.entries()? {
+            println!(
+                "{}  {:>10}  {}",
+                entry.hash,
+                cache::format_bytes(entry.size),
+                entry.format_age(now)
+            );
+        }
+        return Ok(());
+    }
+
+    merge_input_files(&mut args);
+
+    // Snippet commands
+    if handle_snippets(&mut args)? {
+        return Ok(());
+    }
+
     // Show welcome message if no expression and stdin is a terminal
     if args.expression.is_none() {
         if args.files.is_empty() && std::io::stdin().is_terminal() {
@@ -122,23 +232,9 @@ fn run() -> Result<()> {
         ));
     }
 
+    let input_source = resolve_input_source(&args)?;
     let expression = args.expression.unwrap();
 
-    // Determine input format
-    let input_format = if args.parse_csv {
-        InputFormat::Csv
-    } else if args.parse_tsv {
-        InputFormat::Tsv
-    } else if args.parse_json {
-        InputFormat::JsonLines
-    } else {
-        InputFormat::Lines
-    };
-
-    // Create input source
-    let input_source = InputSource::new(args.files.clone(), input_format);
-    input_source.validate()?;
-
     // Determine output format
     let output_format = if let Some(ref fmt) = args.format {
         OutputFormat::from_str(fmt)
@@ -146,6 +242,18 @@ fn run() -> Result<()> {
     } else {
         OutputFormat::default(output::is_terminal())
     };
+    let table_style = TableStyle::from_str(&args.table_style).ok_or_else(|| {
+        LobError::InvalidExpression(format!(
+            "Unknown table style: {} (expected one of: {})",
+            args.table_style,
+            TableStyle::NAMES.join(", ")
+        ))
+    })?;
+    if output_format == OutputFormat::Parquet && !cfg!(feature = "parquet") {
+        return Err(LobError::InvalidExpression(
+            "parquet output requires lob built with `--features parquet`".to_string(),
+        ));
+    }
 
     // Generate code
     let generator = CodeGenerator::new(
@@ -153,22 +261,144 @@ fn run() -> Result<()> {
         input_source.clone(),
         output_format,
         args.stats,
-    );
-    let source = generator.generate()?;
+    )
+    .with_table_style(table_style)
+    .with_stats_interval_ms(args.stats_interval)
+    .with_count_only(args.count)
+    .with_limit(args.limit);
+    if generator.limit_ignored() {
+        eprintln!("Warning: --limit has no effect on an expression that returns a single value");
+    }
+    let (source, span) = generator.generate()?;
 
     if args.show_source {
         println!("{}", source);
         return Ok(());
     }
 
+    if args.explain {
+        let hash = initialize_compiler(args.verbose)?
+            .with_opt_level(args.opt)
+            .cache_key(&Cache::new()?, &source);
+        print!("{}", generator.explain(&hash));
+        return Ok(());
+    }
+
     // Compile and execute
-    compile_and_execute(
-        &expression,
-        &source,
-        &input_source,
-        args.verbose,
-        args.stats,
-    )
+    let options = ExecOptions {
+        opt_level: args.opt,
+        timeout: args.timeout.map(std::time::Duration::from_secs),
+        cache_limit: args.cache_limit,
+        error_format: ErrorFormat::from_str(&args.error_format).unwrap_or_default(),
+        verbose: args.verbose,
+        show_stats: args.stats,
+    };
+    compile_and_execute(&expression, &source, &span, &input_source, &options)
+}
+
+/// Append `--input-file` paths to the positional input files
+fn merge_input_files(args: &mut Args) {
+    let input_files = std::mem::take(&mut args.input_files);
+    args.files.extend(input_files);
+}
+
+/// Build the input source from `--input`/`--parse-*` and the file arguments
+fn resolve_input_source(args: &Args) -> Result<InputSource> {
+    // Determine input format
+    let auto_input = args.input.as_deref() == Some("auto");
+    let input_format = if auto_input {
+        InputFormat::Lines
+    } else if let Some(delimiter) = args.input_delim {
+        if !delimiter.is_ascii() {
+            return Err(LobError::InvalidExpression(format!(
+                "--input-delim must be an ASCII character, got {:?}",
+                delimiter
+            )));
+        }
+        InputFormat::Delimited(delimiter)
+    } else if let Some(separator) = args.split {
+        InputFormat::Split(separator)
+    } else if !args.input_widths.is_empty() {
+        InputFormat::FixedWidth(args.input_widths.clone())
+    } else if let Some(ref fmt) = args.input {
+        InputFormat::from_str(fmt)
+            .ok_or_else(|| LobError::InvalidExpression(format!("Unknown input format: {}", fmt)))?
+    } else if args.parse_csv {
+        InputFormat::Csv
+    } else if args.parse_tsv {
+        InputFormat::Tsv
+    } else if args.parse_json {
+        InputFormat::JsonLines
+    } else {
+        InputFormat::Lines
+    };
+
+    let input_source = if auto_input {
+        InputSource::auto(args.files.clone(), input_format)?
+    } else {
+        InputSource::new(args.files.clone(), input_format)
+    }
+    .with_header(!args.no_header);
+    input_source.validate()?;
+    Ok(input_source)
+}
+
+/// Settings controlling how a generated program is compiled and run
+#[derive(Clone, Copy)]
+struct ExecOptions {
+    /// rustc optimization level
+    opt_level: u8,
+    /// Kill the program after this long
+    timeout: Option<std::time::Duration>,
+    /// Maximum cache size in bytes
+    cache_limit: Option<u64>,
+    /// How compilation errors are reported
+    error_format: ErrorFormat,
+    /// Verbose output
+    verbose: bool,
+    /// Print timing statistics
+    show_stats: bool,
+}
+
+/// Handle `--list-snippets`, `--save` and `--run`
+///
+/// Returns `true` when the command is complete. For `--run`, the snippet is
+/// loaded into `args.expression` and `false` is returned so it executes.
+fn handle_snippets(args: &mut Args) -> Result<bool> {
+    if !(args.list_snippets || args.save.is_some() || args.run.is_some()) {
+        return Ok(false);
+    }
+
+    let store = SnippetStore::new(Cache::new()?.cache_dir().join(snippets::STORE_FILE));
+
+    if args.list_snippets {
+        for (name, expression) in store.list()? {
+            println!("{}\t{}", name, expression);
+        }
+        return Ok(true);
+    }
+
+    if let Some(name) = &args.save {
+        let expression = args
+            .expression
+            .as_deref()
+            .ok_or_else(|| LobError::InvalidExpression("--save needs an expression".to_string()))?;
+        if let Some(previous) = store.save(name, expression)? {
+            eprintln!("Warning: overwrote snippet '{}' (was: {})", name, previous);
+        }
+        println!("Saved snippet '{}'", name);
+        return Ok(true);
+    }
+
+    if let Some(name) = &args.run {
+        // No expression on the command line: the first positional is a file
+        if let Some(file) = args.expression.take() {
+            args.files.insert(0, PathBuf::from(file));
+        }
+        args.expression = Some(store.get(name)?);
+    }
+
+    Ok(false)
 }
 
 /// Initialize the compiler, trying embedded toolchain first, then system rustc
@@ -199,21 +429,40 @@ fn initialize_compiler(verbose: bool) -> Result<Compiler> {
 fn compile_and_execute(
     expression: &str,
     source: &str,
+    span: &ExpressionSpan,
     input_source: &InputSource,
-    verbose: bool,
-    show_stats: bool,
+    options: &ExecOptions,
 ) -> Result<()> {
+    let ExecOptions {
+        opt_level,
+        timeout,
+        cache_limit,
+        error_format,
+        verbose,
+        show_stats,
+    } = *options;
     let cache = Cache::new()?;
-    let compiler = initialize_compiler(verbose)?;
+    let compiler = initialize_compiler(verbose)?
+        .with_opt_level(opt_level)
+        .with_error_format(error_format);
 
     if verbose {
         eprintln!("Compiling expression...");
     }
 
     let compile_start = std::time::Instant::now();
-    let compile_result = compiler.compile_and_cache(source, &cache, Some(expression))?;
+    let compile_result =
+        compiler.compile_and_cache(source, &cache, Some(expression), Some(span))?;
     let compile_time = compile_start.elapsed();
 
+    if let (Some(limit), false) = (cache_limit, compile_result.cache_hit) {
+        // The binary about to run must survive its own eviction pass
+        let evicted = cache.evict_to(limit, Some(&compile_result.hash))?;
+        if verbose && !evicted.is_empty() {
+            eprintln!("Evicted {} cached binaries", evicted.len());
+        }
+    }
+
     if verbose {
         eprintln!("Compiled binary: {:?}", compile_result.binary_path);
         eprintln!("Cache hit: {}", compile_result.cache_hit);
@@ -224,8 +473,12 @@ fn compile_and_execute(
     let exec_start = std::time::Instant::now();
     let mut cmd = Command::new(&compile_result.binary_path);
 
+    // Paths go through the environment so the pipeline's own argv stays free
     if !input_source.is_stdin() {
-        cmd.args(&input_source.files);
+        let paths = std::env::join_paths(&input_source.files).map_err(|e| {
+            LobError::InvalidExpression(format!("cannot pass input file paths: {e}"))
+        })?;
+        cmd.env(lob_prelude::INPUT_FILES_ENV, paths);
     }
 
     let mut child = cmd
@@ -234,7 +487,7 @@ fn compile_and_execute(
         .stderr(std::process::Stdio::inherit())
         .spawn()?;
 
-    let status = child.wait()?;
+    let status = execute::wait_with_timeout(&mut child, timeout)?;
     let exec_time = exec_start.elapsed();
     let total_time = compile_start.elapsed();
 
@@ -263,3 +516,33 @@ fn compile_and_execute(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_input_file_flags_are_collected() {
+        let mut args = Args::try_parse_from([
+            "lob",
+            "--input-file",
+            "a.txt",
+            "_.count()",
+            "b.txt",
+            "--input-file",
+            "dir/c.txt",
+        ])
+        .unwrap();
+        merge_input_files(&mut args);
+        assert_eq!(args.expression.as_deref(), Some("_.count()"));
+        assert_eq!(
+            args.files,
+            vec![
+                PathBuf::from("b.txt"),
+                PathBuf::from("a.txt"),
+                PathBuf::from("dir/c.txt")
+            ]
+        );
+        assert!(args.input_files.is_empty());
+    }
+}
@@ -15,6 +15,12 @@ pub enum OutputFormat {
     Csv,
     /// Table (requires CSV/JSON input)
     Table,
+    /// GitHub-flavored Markdown table (requires CSV/JSON input)
+    Markdown,
+    /// YAML (sequence for iterators, single document for values)
+    Yaml,
+    /// Parquet file written to stdout (requires the `parquet` feature)
+    Parquet,
 }
 
 impl OutputFormat {
@@ -26,10 +32,27 @@ impl OutputFormat {
             "jsonl" | "jsonlines" => Some(Self::JsonLines),
             "csv" => Some(Self::Csv),
             "table" => Some(Self::Table),
+            "md" | "markdown" => Some(Self::Markdown),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "parquet" => Some(Self::Parquet),
             _ => None,
         }
     }
 
+    /// Name as accepted by `--format`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Json => "json",
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+            Self::Table => "table",
+            Self::Markdown => "md",
+            Self::Yaml => "yaml",
+            Self::Parquet => "parquet",
+        }
+    }
+
     /// Get default format based on context
     pub fn default(is_terminal: bool) -> Self {
         if is_terminal {
@@ -40,6 +63,60 @@ impl OutputFormat {
     }
 }
 
+/// Border style used by `--format table`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableStyle {
+    /// Unicode box drawing with rounded corners
+    #[default]
+    Rounded,
+    /// Plain ASCII `+`, `-` and `|`
+    Ascii,
+    /// Unicode box drawing with square corners
+    Sharp,
+    /// Markdown-like pipes
+    Markdown,
+    /// `PostgreSQL` psql style
+    Psql,
+    /// Unicode box drawing with lines between rows
+    Modern,
+    /// No borders
+    Blank,
+}
+
+impl TableStyle {
+    /// Names accepted by `--table-style`
+    pub const NAMES: &'static [&'static str] = &[
+        "rounded", "ascii", "sharp", "markdown", "psql", "modern", "blank",
+    ];
+
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "rounded" => Some(Self::Rounded),
+            "ascii" => Some(Self::Ascii),
+            "sharp" => Some(Self::Sharp),
+            "markdown" => Some(Self::Markdown),
+            "psql" => Some(Self::Psql),
+            "modern" => Some(Self::Modern),
+            "blank" => Some(Self::Blank),
+            _ => None,
+        }
+    }
+
+    /// The `tabled` constructor call for this style
+    pub fn constructor(self) -> &'static str {
+        match self {
+            Self::Rounded => "Style::rounded()",
+            Self::Ascii => "Style::ascii()",
+            Self::Sharp => "Style::sharp()",
+            Self::Markdown => "Style::markdown()",
+            Self::Psql => "Style::psql()",
+            Self::Modern => "Style::modern()",
+            Self::Blank => "Style::blank()",
+        }
+    }
+}
+
 /// Detect if stdout is a terminal
 pub fn is_terminal() -> bool {
     stdout().is_terminal()
@@ -0,0 +1,110 @@
+//! Named snippets: saved expressions that can be rerun by name
+
+use crate::error::{LobError, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// File name of the snippet store inside the cache directory
+pub const STORE_FILE: &str = "snippets.json";
+
+/// JSON file mapping snippet names to expressions
+pub struct SnippetStore {
+    path: PathBuf,
+}
+
+impl SnippetStore {
+    /// Open the store at `path` (the file is created on first save)
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// All snippets, sorted by name
+    pub fn list(&self) -> Result<BTreeMap<String, String>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let data = fs::read_to_string(&self.path)?;
+        serde_json::from_str(&data)
+            .map_err(|e| LobError::Snippet(format!("corrupt snippet store {:?}: {}", self.path, e)))
+    }
+
+    /// Save a snippet, returning the expression it replaced (if any)
+    pub fn save(&self, name: &str, expression: &str) -> Result<Option<String>> {
+        let mut snippets = self.list()?;
+        let previous = snippets.insert(name.to_string(), expression.to_string());
+        let data = serde_json::to_string_pretty(&snippets)
+            .map_err(|e| LobError::Snippet(e.to_string()))?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, data)?;
+        Ok(previous)
+    }
+
+    /// Look up a snippet by name
+    pub fn get(&self, name: &str) -> Result<String> {
+        self.list()?.remove(name).ok_or_else(|| {
+            LobError::Snippet(format!("no snippet named '{}' (see --list-snippets)", name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> SnippetStore {
+        let dir =
+            std::env::temp_dir().join(format!("lob-snippets-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        SnippetStore::new(dir.join(STORE_FILE))
+    }
+
+    #[test]
+    fn save_and_get_round_trip() {
+        let store = temp_store("roundtrip");
+        assert_eq!(
+            store
+                .save("errors", "_.filter(|l| l.contains(\"ERROR\"))")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            store.get("errors").unwrap(),
+            "_.filter(|l| l.contains(\"ERROR\"))"
+        );
+        let _ = fs::remove_dir_all(store.path.parent().unwrap());
+    }
+
+    #[test]
+    fn list_is_sorted_by_name() {
+        let store = temp_store("list");
+        assert!(store.list().unwrap().is_empty());
+        store.save("b", "_.count()").unwrap();
+        store.save("a", "_.take(1)").unwrap();
+        let names: Vec<_> = store.list().unwrap().into_keys().collect();
+        assert_eq!(names, vec!["a", "b"]);
+        let _ = fs::remove_dir_all(store.path.parent().unwrap());
+    }
+
+    #[test]
+    fn overwrite_returns_previous() {
+        let store = temp_store("overwrite");
+        store.save("n", "_.take(1)").unwrap();
+        assert_eq!(
+            store.save("n", "_.take(2)").unwrap(),
+            Some("_.take(1)".to_string())
+        );
+        assert_eq!(store.get("n").unwrap(), "_.take(2)");
+        let _ = fs::remove_dir_all(store.path.parent().unwrap());
+    }
+
+    #[test]
+    fn unknown_name_is_an_error() {
+        let store = temp_store("unknown");
+        let err = store.get("missing").unwrap_err();
+        assert!(matches!(err, LobError::Snippet(_)));
+        assert!(err.to_string().contains("missing"));
+    }
+}
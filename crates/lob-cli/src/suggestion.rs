@@ -26,6 +26,11 @@ pub fn get_suggestion(stderr: &str, user_expr: Option<&str>) -> Option<ErrorSugg
         });
     }
 
+    // Mistakes specific to lob expressions
+    if let Some(sug) = lob_suggestion(stderr, user_expr) {
+        return Some(sug);
+    }
+
     // Cannot find function
     if stderr.contains("cannot find function") {
         if let Some(expr) = user_expr {
@@ -93,3 +98,176 @@ pub fn get_suggestion(stderr: &str, user_expr: Option<&str>) -> Option<ErrorSugg
 
     None
 }
+
+/// Suggestions for mistakes in how lob expressions are written
+fn lob_suggestion(stderr: &str, user_expr: Option<&str>) -> Option<ErrorSuggestion> {
+    // sum()/product() without a result type
+    if stderr.contains("type annotations needed")
+        && (stderr.contains("Sum<") || stderr.contains("Product<"))
+    {
+        return Some(ErrorSuggestion {
+            problem: "sum()/product() needs to know the result type".to_string(),
+            fixes: vec![
+                "Add a turbofish: .sum::<i64>() or .sum::<f64>()".to_string(),
+                "Parse strings first: _.map(|x| x.parse::<i64>().unwrap()).sum::<i64>()"
+                    .to_string(),
+            ],
+        });
+    }
+
+    // collect() without a target collection
+    if stderr.contains("type annotations needed") && stderr.contains("FromIterator") {
+        return Some(ErrorSuggestion {
+            problem: "collect() needs to know which collection to build".to_string(),
+            fixes: vec![
+                "Drop .collect(): iterators are printed directly (as an array with --output json)"
+                    .to_string(),
+                "Name the collection: .collect::<Vec<_>>()".to_string(),
+            ],
+        });
+    }
+
+    // Forgot the `_` input placeholder
+    if let Some(expr) = user_expr.filter(|e| forgot_placeholder(stderr, e)) {
+        let method = expr.trim_start().trim_start_matches('.');
+        return Some(ErrorSuggestion {
+            problem: "Expression doesn't start from the input".to_string(),
+            fixes: vec![
+                format!("Use _ for the input stream: _.{}", method),
+                "Without _, start from a source such as range(0, 10)".to_string(),
+            ],
+        });
+    }
+
+    // Wrong number of closures/closure arguments for a join
+    if user_expr.is_some_and(|e| e.contains(".join_"))
+        && (stderr.contains("closure is expected to take") || stderr.contains("E0061"))
+    {
+        return Some(ErrorSuggestion {
+            problem: "Join takes the other side plus one key function per side".to_string(),
+            fixes: vec![
+                "Each key function takes one item: .join_inner(other, |l| l.0, |r| r.0)"
+                    .to_string(),
+                "Combine the matched pair afterwards: .map(|(l, r)| ...)".to_string(),
+            ],
+        });
+    }
+
+    None
+}
+
+/// Pipeline methods commonly called without the `_` receiver
+const PIPELINE_METHODS: &[&str] = &[
+    "filter",
+    "map",
+    "take",
+    "skip",
+    "count",
+    "sum",
+    "collect",
+    "for_each",
+    "flat_map",
+    "filter_map",
+    "enumerate",
+    "chunk",
+    "window",
+    "unique",
+    "group_by",
+    "join_inner",
+    "join_left",
+    "join_inner_stream",
+    "cross_join",
+    "first",
+    "last",
+    "min",
+    "max",
+];
+
+/// Whether the expression calls a method without a receiver (`filter(...)` or
+/// `.filter(...)`) and rustc failed because of it
+fn forgot_placeholder(stderr: &str, expr: &str) -> bool {
+    let expr = expr.trim_start();
+    if expr.starts_with('.') {
+        return stderr.contains("expected expression");
+    }
+    let name_len = expr
+        .find(|c: char| !(c == '_' || c.is_alphanumeric()))
+        .unwrap_or(expr.len());
+    let name = &expr[..name_len];
+    PIPELINE_METHODS.contains(&name)
+        && expr[name_len..].starts_with('(')
+        && stderr.contains(&format!("cannot find function `{}`", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(stderr: &str, expr: &str) -> String {
+        get_suggestion(stderr, Some(expr)).unwrap().problem
+    }
+
+    #[test]
+    fn sum_without_turbofish() {
+        let stderr =
+            "error[E0283]: type annotations needed\n  = note: cannot satisfy `_: Sum<i64>`";
+        let sug = get_suggestion(stderr, Some("range(0, 5).sum()")).unwrap();
+        assert!(sug.problem.contains("sum()"));
+        assert!(sug.fixes.iter().any(|f| f.contains(".sum::<i64>()")));
+    }
+
+    #[test]
+    fn collect_without_type() {
+        let stderr = "error[E0283]: type annotations needed\n  = note: multiple `impl`s satisfying `_: FromIterator<usize>` found";
+        let sug = get_suggestion(stderr, Some("_.map(|x| x.len()).collect()")).unwrap();
+        assert!(sug.problem.contains("collect()"));
+        assert!(sug.fixes.iter().any(|f| f.contains("Drop .collect()")));
+    }
+
+    #[test]
+    fn missing_placeholder_bare_method() {
+        let stderr = "error[E0425]: cannot find function `filter` in this scope";
+        let sug = get_suggestion(stderr, Some("filter(|x| x.len() > 2)")).unwrap();
+        assert_eq!(sug.problem, "Expression doesn't start from the input");
+        assert_eq!(
+            sug.fixes[0],
+            "Use _ for the input stream: _.filter(|x| x.len() > 2)"
+        );
+    }
+
+    #[test]
+    fn missing_placeholder_leading_dot() {
+        let stderr = "error: expected expression, found `.`";
+        let sug = get_suggestion(stderr, Some(".take(3)")).unwrap();
+        assert_eq!(sug.fixes[0], "Use _ for the input stream: _.take(3)");
+    }
+
+    #[test]
+    fn unknown_function_with_placeholder_is_not_missing_placeholder() {
+        let stderr = "error[E0425]: cannot find function `frobnicate` in this scope";
+        assert_eq!(
+            problem(stderr, "_.map(|x| frobnicate(x))"),
+            "Unknown function or method"
+        );
+    }
+
+    #[test]
+    fn join_closure_arity() {
+        let stderr =
+            "error[E0593]: closure is expected to take 1 argument, but it takes 2 arguments";
+        let sug = get_suggestion(stderr, Some("_.join_inner(other, |a, b| a, |r| r)")).unwrap();
+        assert!(sug.problem.contains("one key function per side"));
+        assert!(sug.fixes.iter().any(|f| f.contains("|l| l.0, |r| r.0")));
+    }
+
+    #[test]
+    fn join_missing_key_function() {
+        let stderr = "error[E0061]: this method takes 3 arguments but 2 arguments were supplied";
+        assert!(problem(stderr, "_.join_left(other, |a| a)").contains("Join"));
+    }
+
+    #[test]
+    fn no_suggestion_for_unrelated_errors() {
+        assert!(get_suggestion("error: something odd", Some("_.count()")).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Lightweight Rust tokenizer for inspecting user expressions
+//!
+//! This is not a full Rust lexer: it only distinguishes what code generation
+//! needs to see, so that string/char literals and comments are never mistaken
+//! for code.
+
+/// Kind of a source token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Identifier or keyword (including `_`)
+    Ident,
+    /// Lifetime or loop label such as `'a` or `'_`
+    Lifetime,
+    /// String, char, byte or numeric literal
+    Literal,
+    /// Line or block comment
+    Comment,
+    /// Whitespace
+    Whitespace,
+    /// Any other single character
+    Punct,
+}
+
+/// A token borrowing its text from the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// Kind of token
+    pub kind: TokenKind,
+    /// Exact source text of the token
+    pub text: &'a str,
+}
+
+impl Token<'_> {
+    /// Whether the token carries no meaning for the parser
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    /// Whether the token is the given punctuation character
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.starts_with(c)
+    }
+}
+
+/// Split source text into tokens
+///
+/// Concatenating the text of every token reproduces the input exactly.
+/// Unterminated literals and comments extend to the end of the input.
+pub fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let start = pos;
+        let rest = &src[pos..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        let kind = if c.is_whitespace() {
+            pos += rest
+                .find(|ch: char| !ch.is_whitespace())
+                .unwrap_or(rest.len());
+            TokenKind::Whitespace
+        } else if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+            TokenKind::Comment
+        } else if rest.starts_with("/*") {
+            pos += block_comment_len(rest);
+            TokenKind::Comment
+        } else if let Some(len) = string_literal_len(rest) {
+            pos += len;
+            TokenKind::Literal
+        } else if c == '\'' {
+            if let Some(len) = char_literal_len(rest) {
+                pos += len;
+                TokenKind::Literal
+            } else {
+                pos += 1 + ident_len(&rest[1..]);
+                TokenKind::Lifetime
+            }
+        } else if c.is_ascii_digit() {
+            pos += ident_len(rest);
+            TokenKind::Literal
+        } else if c == '_' || c.is_alphabetic() {
+            pos += ident_len(rest);
+            TokenKind::Ident
+        } else {
+            pos += c.len_utf8();
+            TokenKind::Punct
+        };
+
+        tokens.push(Token {
+            kind,
+            text: &src[start..pos],
+        });
+    }
+
+    tokens
+}
+
+/// Length of an identifier-like run (letters, digits, `_`)
+fn ident_len(s: &str) -> usize {
+    s.find(|c: char| !(c == '_' || c.is_alphanumeric()))
+        .unwrap_or(s.len())
+}
+
+/// Length of a (possibly nested) block comment
+fn block_comment_len(s: &str) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if s[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if s[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += s[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    s.len()
+}
+
+/// Length of a string literal (`"…"`, `b"…"`, `r#"…"#`, `br"…"`) at the start of `s`
+fn string_literal_len(s: &str) -> Option<usize> {
+    let prefix = if s.starts_with("br") {
+        2
+    } else {
+        usize::from(s.starts_with('b') || s.starts_with('r'))
+    };
+    let raw = s[..prefix].ends_with('r');
+    let body = &s[prefix..];
+
+    if raw {
+        let hashes = body.len() - body.trim_start_matches('#').len();
+        if !body[hashes..].starts_with('"') {
+            return None;
+        }
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let content_start = prefix + hashes + 1;
+        return Some(
+            s[content_start..]
+                .find(&closing)
+                .map_or(s.len(), |end| content_start + end + closing.len()),
+        );
+    }
+
+    if !body.starts_with('"') {
+        return None;
+    }
+    let mut chars = body.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(prefix + i + 1),
+            _ => {}
+        }
+    }
+    Some(s.len())
+}
+
+/// Length of a char literal at the start of `s`, or `None` for a lifetime
+fn char_literal_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        // Escaped char: scan to the closing quote
+        return chars
+            .find(|&(_, c)| c == '\'')
+            .map(|(i, _)| i + 1)
+            .or(Some(s.len()));
+    }
+    match chars.next() {
+        Some((i, '\'')) => Some(i + 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(src)
+            .into_iter()
+            .filter(|t| !t.is_trivia())
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_source() {
+        let src = r#"_.filter(|l| l.contains(".count()")) /* c */ // tail"#;
+        let joined: String = tokenize(src).iter().map(|t| t.text).collect();
+        assert_eq!(joined, src);
+    }
+
+    #[test]
+    fn string_literals_are_single_tokens() {
+        let toks = kinds(r#"x.contains("a \" .sum()")"#);
+        assert!(toks.contains(&(TokenKind::Literal, r#""a \" .sum()""#)));
+        assert!(!toks.iter().any(|(_, t)| *t == "sum"));
+    }
+
+    #[test]
+    fn raw_and_byte_strings() {
+        assert_eq!(
+            kinds(r##"r#"a "quoted" b"# br"x" b"y""##),
+            vec![
+                (TokenKind::Literal, r##"r#"a "quoted" b"#"##),
+                (TokenKind::Literal, r#"br"x""#),
+                (TokenKind::Literal, r#"b"y""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn chars_and_lifetimes() {
+        assert_eq!(
+            kinds(r"'a' '\n' &'_ str"),
+            vec![
+                (TokenKind::Literal, "'a'"),
+                (TokenKind::Literal, r"'\n'"),
+                (TokenKind::Punct, "&"),
+                (TokenKind::Lifetime, "'_"),
+                (TokenKind::Ident, "str"),
+            ]
+        );
+    }
+
+    #[test]
+    fn identifiers_with_underscores() {
+        assert_eq!(
+            kinds("_ foo_bar _private"),
+            vec![
+                (TokenKind::Ident, "_"),
+                (TokenKind::Ident, "foo_bar"),
+                (TokenKind::Ident, "_private"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_block_comment() {
+        let toks = tokenize("/* a /* b */ c */x");
+        assert_eq!(toks[0].kind, TokenKind::Comment);
+        assert_eq!(toks[1].text, "x");
+    }
+}
@@ -555,6 +555,47 @@ fn show_source_csv() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn table_style_unknown_is_rejected_before_compiling() -> Result<()> {
+    lob()
+        .arg("--format")
+        .arg("table")
+        .arg("--table-style")
+        .arg("bogus")
+        .arg("--show-source")
+        .arg("_.take(1)")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown table style: bogus"));
+    Ok(())
+}
+
+#[test]
+fn explain_summarizes_without_compiling() -> Result<()> {
+    lob()
+        .arg("--explain")
+        .arg("--parse-csv")
+        .arg("_.count()")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("terminal operation: yes"))
+        .stdout(predicate::str::contains("input format: csv"))
+        .stdout(predicate::str::contains("cache hash: "));
+    Ok(())
+}
+
+#[test]
+fn count_prints_number_of_items() -> Result<()> {
+    lob()
+        .arg("--count")
+        .arg("_.filter(|l| l.starts_with('a'))")
+        .write_stdin("apple\nbanana\navocado\n")
+        .assert()
+        .success()
+        .stdout("2\n");
+    Ok(())
+}
+
 #[test]
 fn cache_stats() -> Result<()> {
     lob()
@@ -739,6 +780,28 @@ fn cache_miss_then_hit() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn cache_limit_below_binary_size_still_runs() -> Result<()> {
+    let cache_dir =
+        std::env::temp_dir().join(format!("lob_test_cache_limit_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    let _ = fs::create_dir_all(&cache_dir);
+
+    lob()
+        .env("LOB_CACHE_DIR", cache_dir.to_str().unwrap())
+        .args(["--cache-limit", "1K", "_.take(1)"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a"));
+
+    // The fresh binary is kept even though it alone exceeds the limit
+    assert_eq!(fs::read_dir(cache_dir.join("binaries"))?.count(), 1);
+
+    let _ = fs::remove_dir_all(&cache_dir);
+    Ok(())
+}
+
 #[test]
 fn different_exprs_different_results() -> Result<()> {
     let out1 = lob().arg("lob(vec![1,2,3]).count()").output()?;
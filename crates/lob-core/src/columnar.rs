@@ -0,0 +1,154 @@
+//! Columnar collection: `collect_columns` into a `ColumnBatch`
+//!
+//! A minimal column-per-field layout intended as a handoff point to
+//! dataframe tools such as Arrow or Polars.
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// A single cell in a [`ColumnBatch`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    /// Integer value
+    Int(i64),
+    /// Float value
+    Float(f64),
+    /// Boolean value
+    Bool(bool),
+    /// Text value
+    Str(String),
+    /// Missing value
+    Null,
+}
+
+impl From<i64> for ColumnValue {
+    fn from(v: i64) -> Self {
+        Self::Int(v)
+    }
+}
+
+impl From<f64> for ColumnValue {
+    fn from(v: f64) -> Self {
+        Self::Float(v)
+    }
+}
+
+impl From<bool> for ColumnValue {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<String> for ColumnValue {
+    fn from(v: String) -> Self {
+        Self::Str(v)
+    }
+}
+
+impl From<&str> for ColumnValue {
+    fn from(v: &str) -> Self {
+        Self::Str(v.to_string())
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for ColumnValue {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Self::Null, Into::into)
+    }
+}
+
+/// A record that can be split into named fields
+pub trait IntoRow {
+    /// Convert the record into `(field name, value)` pairs
+    fn into_row(self) -> Vec<(String, ColumnValue)>;
+}
+
+impl<S: BuildHasher> IntoRow for HashMap<String, String, S> {
+    fn into_row(self) -> Vec<(String, ColumnValue)> {
+        self.into_iter()
+            .map(|(k, v)| (k, ColumnValue::Str(v)))
+            .collect()
+    }
+}
+
+/// Records stored as one vector of values per field
+///
+/// Columns appear in the order their field was first seen. A field missing
+/// from some rows is filled with [`ColumnValue::Null`], so every column has
+/// exactly [`num_rows`](Self::num_rows) values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnBatch {
+    names: Vec<String>,
+    columns: Vec<Vec<ColumnValue>>,
+    rows: usize,
+}
+
+impl ColumnBatch {
+    /// Create an empty batch
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a record, adding new columns as needed
+    pub fn push_row<R: IntoRow>(&mut self, row: R) {
+        for (name, value) in row.into_row() {
+            let idx = if let Some(idx) = self.names.iter().position(|n| *n == name) {
+                idx
+            } else {
+                self.names.push(name);
+                self.columns.push(vec![ColumnValue::Null; self.rows]);
+                self.names.len() - 1
+            };
+            self.columns[idx].push(value);
+        }
+
+        self.rows += 1;
+        for column in &mut self.columns {
+            column.resize(self.rows, ColumnValue::Null);
+        }
+    }
+
+    /// Number of rows
+    #[must_use]
+    pub fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    #[must_use]
+    pub fn num_columns(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Column names in first-seen order
+    #[must_use]
+    pub fn column_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Values of the named column
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&[ColumnValue]> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .map(|idx| self.columns[idx].as_slice())
+    }
+
+    /// Consume the batch into `(name, values)` pairs
+    #[must_use]
+    pub fn into_columns(self) -> Vec<(String, Vec<ColumnValue>)> {
+        self.names.into_iter().zip(self.columns).collect()
+    }
+}
+
+impl<R: IntoRow> FromIterator<R> for ColumnBatch {
+    fn from_iter<T: IntoIterator<Item = R>>(iter: T) -> Self {
+        let mut batch = Self::new();
+        for row in iter {
+            batch.push_row(row);
+        }
+        batch
+    }
+}
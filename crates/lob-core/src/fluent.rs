@@ -1,9 +1,20 @@
 //! Core Lob wrapper type and fluent API
 
-use crate::grouping::{ChunkIterator, GroupByCollectIterator, WindowIterator};
-use crate::joins::{InnerJoinIterator, LeftJoinIterator};
-use std::collections::HashSet;
-use std::hash::Hash;
+use crate::grouping::{
+    ArrayWindowIterator, BatchingIterator, ByteChunkIterator, ChunkIterator,
+    ConsecutiveGroupIterator, DedupCountsIterator, GroupByCollectIterator, PairsIterator,
+    RollingIterator, StepChunkIterator, WindowIterator, WindowReduceIterator,
+};
+use crate::joins::{AntiJoinIterator, CrossJoinIterator, InnerJoinIterator, LeftJoinIterator};
+use crate::random::SplitMix64;
+use crate::selection::{DedupByKeyIterator, SkipLastIterator, TakeMatchingIterator};
+use crate::statistics::{RunningMedianIterator, Welford};
+use crate::transformation::{
+    DefaultIfEmptyIterator, IntersperseIterator, TeeIterator, WithPositionIterator,
+    ZipLongestIterator,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
 
 /// Main wrapper type for fluent iterator operations
 ///
@@ -37,6 +48,37 @@ impl<I: Iterator> Lob<I> {
         Self { iter }
     }
 
+    /// Run a side effect once on the whole pipeline and pass it on unchanged
+    ///
+    /// Unlike a per-element `inspect`, `f` is called a single time, right
+    /// away, with a reference to the pipeline. Nothing is consumed: to look
+    /// at the items themselves, clone the pipeline inside `f` (possible when
+    /// the wrapped iterator is `Clone`, e.g. a range or a `Vec` iterator;
+    /// iterators returned by other `Lob` adapters are opaque and cannot be
+    /// cloned).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..10)
+    ///     .lob()
+    ///     .tap(|l| eprintln!("input: {} items", l.clone().count()))
+    ///     .filter(|x| x % 3 == 0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![0, 3, 6, 9]);
+    /// ```
+    #[must_use]
+    pub fn tap<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&Self),
+    {
+        f(&self);
+        self
+    }
+
     // ========== Selection Operations (lazy) ==========
 
     /// Filter elements based on a predicate
@@ -99,6 +141,62 @@ impl<I: Iterator> Lob<I> {
         Lob::new(self.iter.skip(n))
     }
 
+    /// Keep only the last n elements, like `tail -n`
+    ///
+    /// Consumes the source immediately, holding at most `n` items in a ring
+    /// buffer, and yields the retained tail in original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..10)
+    ///     .lob()
+    ///     .take_last(3)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![7, 8, 9]);
+    /// ```
+    #[must_use]
+    pub fn take_last(self, n: usize) -> Lob<std::vec::IntoIter<I::Item>> {
+        if n == 0 {
+            return Lob::new(Vec::new().into_iter());
+        }
+
+        let mut tail = VecDeque::with_capacity(n);
+        for item in self.iter {
+            if tail.len() == n {
+                tail.pop_front();
+            }
+            tail.push_back(item);
+        }
+        Lob::new(Vec::from(tail).into_iter())
+    }
+
+    /// Skip the last n elements
+    ///
+    /// Streams with an n-item delay buffer, which makes it handy for dropping
+    /// a trailing footer row. Yields nothing if the stream has n or fewer
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..5)
+    ///     .lob()
+    ///     .skip_last(2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![0, 1, 2]);
+    /// ```
+    #[must_use]
+    pub fn skip_last(self, n: usize) -> Lob<impl Iterator<Item = I::Item>> {
+        Lob::new(SkipLastIterator::new(self.iter, n))
+    }
+
     /// Take elements while predicate is true
     ///
     /// # Examples
@@ -122,6 +220,83 @@ impl<I: Iterator> Lob<I> {
         Lob::new(self.iter.take_while(predicate))
     }
 
+    /// Keep every nth element, starting with the first
+    ///
+    /// Yields the elements at positions 0, n, 2n, ...; the same as the
+    /// standard `step_by(n)`, under a name that is easier to find.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..10).lob().every_nth(3).collect();
+    ///
+    /// assert_eq!(result, vec![0, 3, 6, 9]);
+    /// ```
+    #[must_use]
+    pub fn every_nth(self, n: usize) -> Lob<impl Iterator<Item = I::Item>> {
+        assert!(n > 0, "every_nth step must be greater than 0");
+        Lob::new(self.iter.step_by(n))
+    }
+
+    /// Take elements up to and including the first one matching a predicate
+    ///
+    /// Unlike [`take_while`](Self::take_while), the element that ends the
+    /// stream is kept. Nothing after it is pulled from the source. This is
+    /// [`take_matching`](Self::take_matching) with `n == 1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec!["a", "b", "", "c"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .take_until(|line| line.is_empty())
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec!["a", "b", ""]);
+    /// ```
+    #[must_use]
+    pub fn take_until<F>(self, predicate: F) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        F: FnMut(&I::Item) -> bool,
+    {
+        Lob::new(TakeMatchingIterator::new(self.iter, 1, predicate))
+    }
+
+    /// Yield elements until n of them have matched a predicate
+    ///
+    /// Unlike `filter(pred).take(n)`, non-matching elements are passed through
+    /// too. The stream stops immediately after the nth match, without pulling
+    /// any further element from the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (1..100)
+    ///     .lob()
+    ///     .take_matching(2, |x| x % 3 == 0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    #[must_use]
+    pub fn take_matching<F>(self, n: usize, predicate: F) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        F: FnMut(&I::Item) -> bool,
+    {
+        Lob::new(TakeMatchingIterator::new(self.iter, n, predicate))
+    }
+
     /// Drop elements while predicate is true
     ///
     /// # Examples
@@ -169,52 +344,67 @@ impl<I: Iterator> Lob<I> {
         Lob::new(self.iter.filter(move |item| seen.insert(item.clone())))
     }
 
-    // ========== Transformation Operations (lazy) ==========
-
-    /// Transform each element
+    /// Keep only unique elements, using a custom hasher for the seen-set
+    ///
+    /// Behaves like [`unique`](Self::unique) but lets large dedup jobs plug in
+    /// a faster `BuildHasher` than the default `SipHash`.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
     ///
-    /// let result: Vec<_> = vec![1, 2, 3]
+    /// let result: Vec<_> = vec![1, 2, 2, 3, 1]
     ///     .into_iter()
     ///     .lob()
-    ///     .map(|x| x * 2)
+    ///     .unique_with_hasher(BuildHasherDefault::<DefaultHasher>::default())
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![2, 4, 6]);
+    /// assert_eq!(result, vec![1, 2, 3]);
     /// ```
     #[must_use]
-    pub fn map<F, B>(self, f: F) -> Lob<impl Iterator<Item = B>>
+    pub fn unique_with_hasher<S>(self, hasher: S) -> Lob<impl Iterator<Item = I::Item>>
     where
-        F: FnMut(I::Item) -> B,
+        I::Item: Eq + Hash + Clone,
+        S: BuildHasher,
     {
-        Lob::new(self.iter.map(f))
+        let mut seen = HashSet::with_hasher(hasher);
+        Lob::new(self.iter.filter(move |item| seen.insert(item.clone())))
     }
 
-    /// Add index to each element
+    /// Collapse runs of adjacent elements with equal keys, keeping the first
+    ///
+    /// Only the previous key is remembered, so unlike
+    /// [`unique`](Self::unique) an item is kept again if its key reappears
+    /// after a different one.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = vec!["a", "b", "c"]
+    /// let result: Vec<_> = vec![(1, "a"), (1, "b"), (2, "c"), (1, "d")]
     ///     .into_iter()
     ///     .lob()
-    ///     .enumerate()
+    ///     .dedup_by_key(|x| x.0)
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![(0, "a"), (1, "b"), (2, "c")]);
+    /// assert_eq!(result, vec![(1, "a"), (2, "c"), (1, "d")]);
     /// ```
     #[must_use]
-    pub fn enumerate(self) -> Lob<impl Iterator<Item = (usize, I::Item)>> {
-        Lob::new(self.iter.enumerate())
+    pub fn dedup_by_key<K, F>(self, key_fn: F) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        K: PartialEq,
+        F: FnMut(&I::Item) -> K,
+    {
+        Lob::new(DedupByKeyIterator::new(self.iter, key_fn))
     }
 
-    /// Zip with another iterator
+    // ========== Transformation Operations (lazy) ==========
+
+    /// Transform each element
     ///
     /// # Examples
     ///
@@ -224,225 +414,1203 @@ impl<I: Iterator> Lob<I> {
     /// let result: Vec<_> = vec![1, 2, 3]
     ///     .into_iter()
     ///     .lob()
-    ///     .zip(vec!["a", "b", "c"])
+    ///     .map(|x| x * 2)
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![(1, "a"), (2, "b"), (3, "c")]);
+    /// assert_eq!(result, vec![2, 4, 6]);
     /// ```
     #[must_use]
-    pub fn zip<J>(self, other: J) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    pub fn map<F, B>(self, f: F) -> Lob<impl Iterator<Item = B>>
     where
-        J: IntoIterator,
+        F: FnMut(I::Item) -> B,
     {
-        Lob::new(self.iter.zip(other))
+        Lob::new(self.iter.map(f))
     }
 
-    /// Flatten nested iterators
+    /// Transform elements until the function returns `None`
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = vec![vec![1, 2], vec![3, 4]]
+    /// let result: Vec<i32> = vec!["1", "2", "x", "3"]
     ///     .into_iter()
     ///     .lob()
-    ///     .flatten()
+    ///     .map_while(|s| s.parse().ok())
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![1, 2, 3, 4]);
+    /// assert_eq!(result, vec![1, 2]);
     /// ```
     #[must_use]
-    pub fn flatten<T>(self) -> Lob<impl Iterator<Item = T>>
+    pub fn map_while<B, F>(self, f: F) -> Lob<impl Iterator<Item = B>>
     where
-        I::Item: IntoIterator<Item = T>,
+        F: FnMut(I::Item) -> Option<B>,
     {
-        Lob::new(self.iter.flatten())
+        Lob::new(self.iter.map_while(f))
     }
 
-    // ========== Grouping Operations ==========
-
-    /// Group elements into chunks of size n
+    /// Map elements in parallel on the rayon thread pool
+    ///
+    /// The source is collected first, then `f` runs across threads. Results
+    /// come back in the original order, exactly as with `map`, so this is a
+    /// drop-in replacement for CPU-heavy transforms. Requires the `rayon`
+    /// feature.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = (0..5)
-    ///     .lob()
-    ///     .chunk(2)
-    ///     .collect();
+    /// let squares: Vec<_> = (0..5).lob().par_map(|x| x * x).collect();
     ///
-    /// assert_eq!(result, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    /// assert_eq!(squares, vec![0, 1, 4, 9, 16]);
     /// ```
+    #[cfg(feature = "rayon")]
     #[must_use]
-    pub fn chunk(self, n: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>> {
-        Lob::new(ChunkIterator::new(self.iter, n))
+    pub fn par_map<F, B>(self, f: F) -> Lob<impl Iterator<Item = B>>
+    where
+        I::Item: Send,
+        B: Send,
+        F: Fn(I::Item) -> B + Sync + Send,
+    {
+        use rayon::prelude::*;
+
+        let items: Vec<I::Item> = self.iter.collect();
+        let results: Vec<B> = items.into_par_iter().map(f).collect();
+        Lob::new(results.into_iter())
     }
 
-    /// Create sliding windows of size n
+    /// Add index to each element
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = (1..=4)
+    /// let result: Vec<_> = vec!["a", "b", "c"]
+    ///     .into_iter()
     ///     .lob()
-    ///     .window(2)
+    ///     .enumerate()
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![vec![1, 2], vec![2, 3], vec![3, 4]]);
+    /// assert_eq!(result, vec![(0, "a"), (1, "b"), (2, "c")]);
     /// ```
     #[must_use]
-    pub fn window(self, n: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
-    where
-        I::Item: Clone,
-    {
-        Lob::new(WindowIterator::new(self.iter, n))
+    pub fn enumerate(self) -> Lob<impl Iterator<Item = (usize, I::Item)>> {
+        Lob::new(self.iter.enumerate())
     }
 
-    /// Group elements by a key function
+    /// Add an index starting at `start` to each element
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = vec![1, 2, 3, 4, 5, 6]
+    /// let result: Vec<_> = vec!["a", "b"]
     ///     .into_iter()
     ///     .lob()
-    ///     .group_by(|x| x % 2)
+    ///     .enumerate_from(1)
     ///     .collect();
     ///
-    /// // Result contains (key, group) pairs
-    /// assert_eq!(result.len(), 2);
+    /// assert_eq!(result, vec![(1, "a"), (2, "b")]);
     /// ```
     #[must_use]
-    pub fn group_by<K, F>(self, key_fn: F) -> Lob<impl Iterator<Item = (K, Vec<I::Item>)>>
-    where
-        K: Eq + Hash,
-        F: FnMut(&I::Item) -> K,
-    {
-        Lob::new(GroupByCollectIterator::new(self.iter, key_fn))
+    pub fn enumerate_from(self, start: usize) -> Lob<impl Iterator<Item = (usize, I::Item)>> {
+        Lob::new(self.iter.enumerate().map(move |(i, x)| (i + start, x)))
     }
 
-    // ========== Join Operations ==========
-
-    /// Inner join with another iterator based on key functions
+    /// Pair each element with a watermark: the running maximum of `f`
+    ///
+    /// The watermark never decreases, so late (out-of-order) elements carry
+    /// the highest value seen so far rather than their own.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let left = vec![(1, "a"), (2, "b"), (3, "c")];
-    /// let right = vec![(1, "x"), (2, "y"), (4, "z")];
-    ///
-    /// let result: Vec<_> = left
+    /// let result: Vec<_> = vec![1, 3, 2, 5]
     ///     .into_iter()
     ///     .lob()
-    ///     .join_inner(right, |x| x.0, |x| x.0)
+    ///     .with_watermark(|t| *t)
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![((1, "a"), (1, "x")), ((2, "b"), (2, "y"))]);
+    /// assert_eq!(result, vec![(1, 1), (3, 3), (2, 3), (5, 5)]);
     /// ```
     #[must_use]
-    pub fn join_inner<J, K, FL, FR>(
-        self,
-        other: J,
-        left_key: FL,
-        right_key: FR,
-    ) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    pub fn with_watermark<W, F>(self, mut f: F) -> Lob<impl Iterator<Item = (I::Item, W)>>
     where
-        I::Item: Clone,
-        J: IntoIterator,
-        J::Item: Clone,
-        K: Eq + Hash,
-        FL: Fn(&I::Item) -> K,
-        FR: Fn(&J::Item) -> K,
+        W: PartialOrd + Clone,
+        F: FnMut(&I::Item) -> W,
     {
-        Lob::new(InnerJoinIterator::new(
-            self.iter, other, left_key, right_key,
-        ))
+        let mut watermark: Option<W> = None;
+        Lob::new(self.iter.map(move |item| {
+            let value = f(&item);
+            let current = match watermark.take() {
+                Some(w) if w >= value => w,
+                _ => value,
+            };
+            watermark = Some(current.clone());
+            (item, current)
+        }))
     }
 
-    /// Left join with another iterator based on key functions
+    /// Split into two independent streams over the same elements
+    ///
+    /// Both halves yield every element. Items pulled by the leading half are
+    /// cloned into a shared buffer until the other half catches up, so memory
+    /// grows with the distance between the two consumers: draining one half
+    /// completely before touching the other buffers the whole stream.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let left = vec![(1, "a"), (2, "b"), (3, "c")];
-    /// let right = vec![(1, "x"), (2, "y")];
-    ///
-    /// let result: Vec<_> = left
-    ///     .into_iter()
-    ///     .lob()
-    ///     .join_left(right, |x| x.0, |x| x.0)
-    ///     .collect();
+    /// let (a, b) = (1..=4).lob().tee();
     ///
-    /// assert_eq!(result.len(), 3);  // All left items preserved
+    /// assert_eq!(a.count(), 4);
+    /// assert_eq!(b.sum::<i32>(), 10);
     /// ```
     #[must_use]
-    pub fn join_left<J, K, FL, FR>(
+    pub fn tee(
         self,
-        other: J,
-        left_key: FL,
-        right_key: FR,
-    ) -> Lob<impl Iterator<Item = (I::Item, Option<J::Item>)>>
+    ) -> (
+        Lob<impl Iterator<Item = I::Item>>,
+        Lob<impl Iterator<Item = I::Item>>,
+    )
     where
         I::Item: Clone,
-        J: IntoIterator,
-        J::Item: Clone,
-        K: Eq + Hash,
-        FL: Fn(&I::Item) -> K,
-        FR: Fn(&J::Item) -> K,
     {
-        Lob::new(LeftJoinIterator::new(self.iter, other, left_key, right_key))
+        let (left, right) = TeeIterator::new(self.iter);
+        (Lob::new(left), Lob::new(right))
     }
 
-    // ========== Terminal Operations (consume iterator) ==========
-
-    /// Collect into a collection
+    /// Insert a separator between consecutive elements
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let result: Vec<_> = (0..5)
+    /// let result: Vec<_> = vec![1, 2, 3]
+    ///     .into_iter()
     ///     .lob()
-    ///     .filter(|x| x % 2 == 0)
+    ///     .intersperse(0)
     ///     .collect();
     ///
-    /// assert_eq!(result, vec![0, 2, 4]);
+    /// assert_eq!(result, vec![1, 0, 2, 0, 3]);
     /// ```
-    pub fn collect<B: FromIterator<I::Item>>(self) -> B {
-        self.iter.collect()
+    #[must_use]
+    pub fn intersperse(self, sep: I::Item) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(IntersperseIterator::new(self.iter, sep))
     }
 
-    /// Count the number of elements
+    /// Tag each element with its [`Position`](crate::Position) in the stream
+    ///
+    /// Looks one element ahead to recognize the last one, which makes
+    /// "separator between but not after" formatting straightforward.
     ///
     /// # Examples
     ///
     /// ```
-    /// use lob_core::LobExt;
+    /// use lob_core::{LobExt, Position};
     ///
-    /// let count = (0..10)
+    /// let line: String = vec!["a", "b", "c"]
+    ///     .into_iter()
     ///     .lob()
-    ///     .filter(|x| x % 2 == 0)
-    ///     .count();
+    ///     .with_position()
+    ///     .map(|(pos, s)| match pos {
+    ///         Position::Last | Position::Only => s.to_string(),
+    ///         _ => format!("{s}, "),
+    ///     })
+    ///     .collect();
     ///
-    /// assert_eq!(count, 5);
+    /// assert_eq!(line, "a, b, c");
     /// ```
-    pub fn count(self) -> usize {
-        self.iter.count()
+    #[must_use]
+    pub fn with_position(self) -> Lob<impl Iterator<Item = (crate::Position, I::Item)>> {
+        Lob::new(WithPositionIterator::new(self.iter))
     }
 
-    /// Sum all elements
+    /// Yield `value` once if the stream is empty, otherwise pass it through
+    ///
+    /// Handy for giving table output a placeholder row when a filter
+    /// removes everything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let rows: Vec<_> = (1..10).lob().filter(|x| *x > 100).default_if_empty(0).collect();
+    ///
+    /// assert_eq!(rows, vec![0]);
+    /// ```
+    #[must_use]
+    pub fn default_if_empty(self, value: I::Item) -> Lob<impl Iterator<Item = I::Item>> {
+        Lob::new(DefaultIfEmptyIterator::new(self.iter, value))
+    }
+
+    /// Repeat the elements endlessly
+    ///
+    /// **The result is infinite** (unless the source is empty): bound it with
+    /// `take`, `zip` against a finite stream, or another short-circuiting
+    /// operation, or a terminal such as `count` will never return.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let labels: Vec<_> = (1..=5)
+    ///     .lob()
+    ///     .zip(vec!["red", "blue"].into_iter().lob().cycle())
+    ///     .collect();
+    ///
+    /// assert_eq!(labels, vec![(1, "red"), (2, "blue"), (3, "red"), (4, "blue"), (5, "red")]);
+    /// ```
+    #[must_use]
+    pub fn cycle(self) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        I: Clone,
+    {
+        Lob::new(self.iter.cycle())
+    }
+
+    /// Zip with another iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 2, 3]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .zip(vec!["a", "b", "c"])
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![(1, "a"), (2, "b"), (3, "c")]);
+    /// ```
+    #[must_use]
+    pub fn zip<J>(self, other: J) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    where
+        J: IntoIterator,
+    {
+        Lob::new(self.iter.zip(other))
+    }
+
+    /// Zip with another iterator, continuing until both are exhausted
+    ///
+    /// The depleted side is padded with `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 2]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .zip_longest(vec!["a"])
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![(Some(1), Some("a")), (Some(2), None)]);
+    /// ```
+    #[must_use]
+    #[allow(clippy::type_complexity)]
+    pub fn zip_longest<J>(
+        self,
+        other: J,
+    ) -> Lob<impl Iterator<Item = (Option<I::Item>, Option<J::Item>)>>
+    where
+        J: IntoIterator,
+    {
+        Lob::new(ZipLongestIterator::new(self.iter, other.into_iter()))
+    }
+
+    /// Flatten nested iterators
+    ///
+    /// Removes exactly one level of nesting: a stream of `Vec<T>` becomes a
+    /// stream of `T`, while a stream of `Vec<Vec<T>>` becomes a stream of
+    /// `Vec<T>`. Call `flatten` once per level to go deeper.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![vec![1, 2], vec![3, 4]]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .flatten()
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![1, 2, 3, 4]);
+    /// ```
+    ///
+    /// Two levels of nesting need two calls:
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let nested = vec![vec![vec![1, 2], vec![3]], vec![vec![4]]];
+    ///
+    /// let once: Vec<Vec<i32>> = nested.clone().into_iter().lob().flatten().collect();
+    /// assert_eq!(once, vec![vec![1, 2], vec![3], vec![4]]);
+    ///
+    /// let twice: Vec<i32> = nested.into_iter().lob().flatten().flatten().collect();
+    /// assert_eq!(twice, vec![1, 2, 3, 4]);
+    /// ```
+    #[must_use]
+    pub fn flatten<T>(self) -> Lob<impl Iterator<Item = T>>
+    where
+        I::Item: IntoIterator<Item = T>,
+    {
+        Lob::new(self.iter.flatten())
+    }
+
+    /// Keep the payloads of `Some` items, dropping `None`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![Some(1), None, Some(3)]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .flatten_opt()
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![1, 3]);
+    /// ```
+    #[must_use]
+    pub fn flatten_opt<T>(self) -> Lob<impl Iterator<Item = T>>
+    where
+        I: Iterator<Item = Option<T>>,
+    {
+        Lob::new(self.iter.flatten())
+    }
+
+    /// Keep the values of `Ok` items, dropping errors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<i32> = vec!["1", "x", "3"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .map(str::parse)
+    ///     .flatten_ok()
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![1, 3]);
+    /// ```
+    #[must_use]
+    pub fn flatten_ok<T, E>(self) -> Lob<impl Iterator<Item = T>>
+    where
+        I: Iterator<Item = Result<T, E>>,
+    {
+        Lob::new(self.iter.flatten())
+    }
+
+    /// Emit the median of all elements seen so far, one value per element
+    ///
+    /// Uses two heaps for O(log n) work per element without re-sorting. For an
+    /// even count the median is the mean of the two middle values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![5, 1, 3]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .running_median()
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![5.0, 3.0, 3.0]);
+    /// ```
+    #[must_use]
+    pub fn running_median(self) -> Lob<impl Iterator<Item = f64>>
+    where
+        I::Item: Into<f64>,
+    {
+        Lob::new(RunningMedianIterator::new(self.iter))
+    }
+
+    /// Sort all elements with a comparator
+    ///
+    /// Consumes the source immediately. The sort is stable, so equal elements
+    /// keep their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![3, 1, 2]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .sorted_by(|a, b| b.cmp(a))
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![3, 2, 1]);
+    /// ```
+    #[must_use]
+    pub fn sorted_by<F>(self, compare: F) -> Lob<std::vec::IntoIter<I::Item>>
+    where
+        F: FnMut(&I::Item, &I::Item) -> std::cmp::Ordering,
+    {
+        let mut items: Vec<_> = self.iter.collect();
+        items.sort_by(compare);
+        Lob::new(items.into_iter())
+    }
+
+    /// Randomly reorder all elements
+    ///
+    /// Buffers the entire stream, then applies a Fisher–Yates shuffle. The
+    /// order differs from run to run; use
+    /// [`shuffle_seeded`](Self::shuffle_seeded) for reproducible output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let mut result: Vec<_> = (0..5).lob().shuffle().collect();
+    /// result.sort_unstable();
+    ///
+    /// assert_eq!(result, vec![0, 1, 2, 3, 4]);
+    /// ```
+    #[must_use]
+    pub fn shuffle(self) -> Lob<std::vec::IntoIter<I::Item>> {
+        self.shuffle_with(&mut SplitMix64::from_entropy())
+    }
+
+    /// Randomly reorder all elements from a fixed seed
+    ///
+    /// Like [`shuffle`](Self::shuffle) (and likewise buffers the entire
+    /// stream), but the same seed and input always give the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let a: Vec<_> = (0..10).lob().shuffle_seeded(3).collect();
+    /// let b: Vec<_> = (0..10).lob().shuffle_seeded(3).collect();
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    #[must_use]
+    pub fn shuffle_seeded(self, seed: u64) -> Lob<std::vec::IntoIter<I::Item>> {
+        self.shuffle_with(&mut SplitMix64::new(seed))
+    }
+
+    fn shuffle_with(self, rng: &mut SplitMix64) -> Lob<std::vec::IntoIter<I::Item>> {
+        let mut items: Vec<_> = self.iter.collect();
+        for i in (1..items.len()).rev() {
+            items.swap(i, rng.below(i + 1));
+        }
+        Lob::new(items.into_iter())
+    }
+
+    /// Collect all elements so the pipeline can be cloned and iterated again
+    ///
+    /// Lazy adapter chains are usually not `Clone`; the materialized `Lob`
+    /// is, so one result can feed several terminals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let m = (1..=4).lob().map(|x| x * 10).materialize();
+    /// let total: i32 = m.clone().sum();
+    ///
+    /// assert_eq!(total, 100);
+    /// assert_eq!(m.count(), 4);
+    /// ```
+    #[must_use]
+    pub fn materialize(self) -> Lob<std::vec::IntoIter<I::Item>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(self.iter.collect::<Vec<_>>().into_iter())
+    }
+
+    // ========== Grouping Operations ==========
+
+    /// Group elements into chunks of size n
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..5)
+    ///     .lob()
+    ///     .chunk(2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    /// ```
+    #[must_use]
+    pub fn chunk(self, n: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>> {
+        Lob::new(ChunkIterator::new(self.iter, n))
+    }
+
+    /// Group elements into chunks of `size`, starting a new chunk every `step` elements
+    ///
+    /// With `step == size` this behaves like [`chunk`](Self::chunk); a smaller
+    /// step makes chunks overlap and a larger step skips elements between
+    /// chunks. The last chunk may be shorter than `size`, but a trailing chunk
+    /// that would only repeat already-emitted elements is not produced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `step` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..6)
+    ///     .lob()
+    ///     .chunk_step(3, 2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec![0, 1, 2], vec![2, 3, 4], vec![4, 5]]);
+    /// ```
+    #[must_use]
+    pub fn chunk_step(self, size: usize, step: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(StepChunkIterator::new(self.iter, size, step))
+    }
+
+    /// Group strings into chunks whose total length is at most `max_bytes`
+    ///
+    /// Items are added to a chunk until the next one would push it over the
+    /// budget. A single item longer than `max_bytes` is emitted as a chunk of
+    /// its own rather than being split or dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec!["aaa", "bb", "c", "dddddd", "e"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .chunk_bytes(5)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec!["aaa", "bb"], vec!["c"], vec!["dddddd"], vec!["e"]]);
+    /// ```
+    #[must_use]
+    pub fn chunk_bytes(self, max_bytes: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
+    where
+        I::Item: AsRef<str>,
+    {
+        Lob::new(ByteChunkIterator::new(self.iter, max_bytes))
+    }
+
+    /// Group elements with a closure that pulls from the source directly
+    ///
+    /// Each call to `f` receives the underlying iterator and may consume as
+    /// many items as it likes to build one output; returning `None` ends the
+    /// stream. Wrap the source in `peekable()` to decide without consuming.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// // Pairs, with a short final batch
+    /// let result: Vec<Vec<i32>> = (1..=5)
+    ///     .lob()
+    ///     .batching(|it| {
+    ///         let first = it.next()?;
+    ///         Some(std::iter::once(first).chain(it.next()).collect())
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    /// ```
+    #[must_use]
+    pub fn batching<B, F>(self, f: F) -> Lob<impl Iterator<Item = B>>
+    where
+        F: FnMut(&mut I) -> Option<B>,
+    {
+        Lob::new(BatchingIterator::new(self.iter, f))
+    }
+
+    /// Create sliding windows of size n
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (1..=4)
+    ///     .lob()
+    ///     .window(2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec![1, 2], vec![2, 3], vec![3, 4]]);
+    /// ```
+    #[must_use]
+    pub fn window(self, n: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(WindowIterator::new(self.iter, n))
+    }
+
+    /// Create sliding windows of `size`, advancing `step` elements between
+    /// windows
+    ///
+    /// With `step == 1` this behaves like [`window`](Self::window); with
+    /// `step == size` windows do not overlap and a larger step skips elements
+    /// between windows. Only full windows are produced, so trailing elements
+    /// that do not fill a window are dropped (unlike
+    /// [`chunk_step`](Self::chunk_step)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `step` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (1..=5)
+    ///     .lob()
+    ///     .window_step(2, 2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec![1, 2], vec![3, 4]]);
+    /// ```
+    #[must_use]
+    pub fn window_step(self, size: usize, step: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(WindowIterator::with_step(self.iter, size, step))
+    }
+
+    /// Iterate over adjacent pairs as tuples
+    ///
+    /// Like `window(2)` but yields `(a, b)` tuples, so no `Vec` is allocated
+    /// per pair. Streams with fewer than two elements yield nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let deltas: Vec<_> = vec![1, 4, 9, 16]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .pairs()
+    ///     .map(|(a, b)| b - a)
+    ///     .collect();
+    ///
+    /// assert_eq!(deltas, vec![3, 5, 7]);
+    /// ```
+    #[must_use]
+    pub fn pairs(self) -> Lob<impl Iterator<Item = (I::Item, I::Item)>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(PairsIterator::new(self.iter))
+    }
+
+    /// Apply a function to each sliding window of size n
+    ///
+    /// Produces one output per full window, like `window(n).map(f)` but
+    /// without building a `Vec` for every window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<i32> = (1..=5)
+    ///     .lob()
+    ///     .window_reduce(3, |w| w.iter().sum())
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![6, 9, 12]);
+    /// ```
+    #[must_use]
+    pub fn window_reduce<B, F>(self, n: usize, f: F) -> Lob<impl Iterator<Item = B>>
+    where
+        F: FnMut(&VecDeque<I::Item>) -> B,
+    {
+        Lob::new(WindowReduceIterator::new(self.iter, n, f))
+    }
+
+    /// Apply a function to each sliding window of size n as a slice
+    ///
+    /// The general primitive behind rolling sums, means and extrema. Unlike
+    /// [`window_reduce`](Self::window_reduce) the window is a contiguous
+    /// `&[T]`, backed by a buffer of at most `2 * n` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// // Rolling range (max - min)
+    /// let result: Vec<i32> = vec![3, 1, 4, 1, 5, 9]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .rolling(3, |w| w.iter().max().unwrap() - w.iter().min().unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![3, 3, 4, 8]);
+    /// ```
+    #[must_use]
+    pub fn rolling<B, F>(self, n: usize, f: F) -> Lob<impl Iterator<Item = B>>
+    where
+        F: FnMut(&[I::Item]) -> B,
+    {
+        Lob::new(RollingIterator::new(self.iter, n, f))
+    }
+
+    /// Moving average over sliding windows of size n
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 2, 3, 4]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .moving_average(2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![1.5, 2.5, 3.5]);
+    /// ```
+    #[must_use]
+    pub fn moving_average(self, n: usize) -> Lob<impl Iterator<Item = f64>>
+    where
+        I::Item: Clone + Into<f64>,
+    {
+        self.window_reduce(n, |w| {
+            w.iter().cloned().map(Into::into).sum::<f64>() / w.len() as f64
+        })
+    }
+
+    /// Create sliding windows of a fixed size `N` as arrays
+    ///
+    /// Like [`window`](Self::window) but yields `[T; N]`, avoiding a heap
+    /// allocation per window. A stream shorter than `N` yields nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (1..=4)
+    ///     .lob()
+    ///     .window_array::<2>()
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![[1, 2], [2, 3], [3, 4]]);
+    /// ```
+    #[must_use]
+    pub fn window_array<const N: usize>(self) -> Lob<impl Iterator<Item = [I::Item; N]>>
+    where
+        I::Item: Clone,
+    {
+        Lob::new(ArrayWindowIterator::<I, N>::new(self.iter))
+    }
+
+    /// Create overlapping n-grams of size n
+    ///
+    /// Equivalent to [`window`](Self::window), named for text-analysis use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec!["the", "cat", "sat"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .ngrams(2)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![vec!["the", "cat"], vec!["cat", "sat"]]);
+    /// ```
+    #[must_use]
+    pub fn ngrams(self, n: usize) -> Lob<impl Iterator<Item = Vec<I::Item>>>
+    where
+        I::Item: Clone,
+    {
+        self.window(n)
+    }
+
+    /// Group elements by a key function
+    ///
+    /// Groups are yielded in the order their keys first appear in the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 2, 3, 4, 5, 6]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .group_by(|x| x % 2)
+    ///     .collect();
+    ///
+    /// // Result contains (key, group) pairs in first-seen key order
+    /// assert_eq!(result, vec![(1, vec![1, 3, 5]), (0, vec![2, 4, 6])]);
+    /// ```
+    #[must_use]
+    pub fn group_by<K, F>(self, key_fn: F) -> Lob<impl Iterator<Item = (K, Vec<I::Item>)>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&I::Item) -> K,
+    {
+        Lob::new(GroupByCollectIterator::new(self.iter, key_fn))
+    }
+
+    /// Group runs of adjacent elements that share a key
+    ///
+    /// Unlike [`group_by`](Self::group_by), this streams: each group is
+    /// emitted as soon as the key changes, so memory is bounded by the largest
+    /// run. Input should be sorted by key; a key that reappears later starts a
+    /// new group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 1, 2, 1]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .group_consecutive(|x| *x)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![(1, vec![1, 1]), (2, vec![2]), (1, vec![1])]);
+    /// ```
+    #[must_use]
+    pub fn group_consecutive<K, F>(self, key_fn: F) -> Lob<impl Iterator<Item = (K, Vec<I::Item>)>>
+    where
+        K: PartialEq,
+        F: FnMut(&I::Item) -> K,
+    {
+        Lob::new(ConsecutiveGroupIterator::new(self.iter, key_fn))
+    }
+
+    /// Collapse runs of equal adjacent items into `(run_length, item)`, like `uniq -c`
+    ///
+    /// Streams with constant memory; sort first to count every occurrence
+    /// of an item rather than each run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec!["a", "a", "b", "a"].into_iter().lob().dedup_counts().collect();
+    ///
+    /// assert_eq!(result, vec![(2, "a"), (1, "b"), (1, "a")]);
+    /// ```
+    #[must_use]
+    pub fn dedup_counts(self) -> Lob<impl Iterator<Item = (usize, I::Item)>>
+    where
+        I::Item: PartialEq,
+    {
+        Lob::new(DedupCountsIterator::new(self.iter))
+    }
+
+    // ========== Join Operations ==========
+
+    /// Inner join with another iterator based on key functions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let left = vec![(1, "a"), (2, "b"), (3, "c")];
+    /// let right = vec![(1, "x"), (2, "y"), (4, "z")];
+    ///
+    /// let result: Vec<_> = left
+    ///     .into_iter()
+    ///     .lob()
+    ///     .join_inner(right, |x| x.0, |x| x.0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![((1, "a"), (1, "x")), ((2, "b"), (2, "y"))]);
+    /// ```
+    ///
+    /// Composite keys work by returning a tuple from both key functions.
+    /// Keys are owned values, so copyable fields (numbers, `&'static str`)
+    /// avoid allocation while `String` fields need a clone:
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let sales = vec![("eu", 1, 100), ("us", 1, 200), ("eu", 2, 150)];
+    /// let targets = vec![("eu", 1, 90), ("eu", 2, 160)];
+    ///
+    /// let result: Vec<_> = sales
+    ///     .into_iter()
+    ///     .lob()
+    ///     .join_inner(targets, |s| (s.0, s.1), |t| (t.0, t.1))
+    ///     .map(|(s, t)| (s.0, s.1, s.2 >= t.2))
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![("eu", 1, true), ("eu", 2, false)]);
+    /// ```
+    #[must_use]
+    pub fn join_inner<J, K, FL, FR>(
+        self,
+        other: J,
+        left_key: FL,
+        right_key: FR,
+    ) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    where
+        I::Item: Clone,
+        J: IntoIterator,
+        J::Item: Clone,
+        K: Eq + Hash,
+        FL: Fn(&I::Item) -> K,
+        FR: Fn(&J::Item) -> K,
+    {
+        Lob::new(InnerJoinIterator::new(
+            self.iter, other, left_key, right_key,
+        ))
+    }
+
+    /// Inner join that buffers this side and streams the other
+    ///
+    /// Produces the same pairs as [`join_inner`](Self::join_inner), but the
+    /// hash table is built from `self` while `other` is consumed lazily. Use
+    /// it when this side is small and the other is large (or unbounded).
+    /// Pairs come in the order of `other`; for each of its items, matching
+    /// left items appear in their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let wanted = vec![(2, "b"), (4, "d")];
+    ///
+    /// let result: Vec<_> = wanted
+    ///     .into_iter()
+    ///     .lob()
+    ///     .join_inner_stream((0..1_000).map(|i| (i, i * i)), |w| w.0, |r| r.0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![((2, "b"), (2, 4)), ((4, "d"), (4, 16))]);
+    /// ```
+    #[must_use]
+    pub fn join_inner_stream<J, K, FL, FR>(
+        self,
+        other: J,
+        left_key: FL,
+        right_key: FR,
+    ) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    where
+        I::Item: Clone,
+        J: IntoIterator,
+        J::Item: Clone,
+        K: Eq + Hash,
+        FL: Fn(&I::Item) -> K,
+        FR: Fn(&J::Item) -> K,
+    {
+        // Same join with the roles swapped: `other` drives, `self` is indexed
+        Lob::new(
+            InnerJoinIterator::new(other.into_iter(), self.iter, right_key, left_key)
+                .map(|(right, left)| (left, right)),
+        )
+    }
+
+    /// Left join with another iterator based on key functions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let left = vec![(1, "a"), (2, "b"), (3, "c")];
+    /// let right = vec![(1, "x"), (2, "y")];
+    ///
+    /// let result: Vec<_> = left
+    ///     .into_iter()
+    ///     .lob()
+    ///     .join_left(right, |x| x.0, |x| x.0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result.len(), 3);  // All left items preserved
+    /// ```
+    ///
+    /// As with [`join_inner`](Self::join_inner), composite keys are expressed
+    /// as tuples returned from the key functions.
+    #[must_use]
+    pub fn join_left<J, K, FL, FR>(
+        self,
+        other: J,
+        left_key: FL,
+        right_key: FR,
+    ) -> Lob<impl Iterator<Item = (I::Item, Option<J::Item>)>>
+    where
+        I::Item: Clone,
+        J: IntoIterator,
+        J::Item: Clone,
+        K: Eq + Hash,
+        FL: Fn(&I::Item) -> K,
+        FR: Fn(&J::Item) -> K,
+    {
+        Lob::new(LeftJoinIterator::new(self.iter, other, left_key, right_key))
+    }
+
+    /// Anti join: keep left items whose key has no match in another iterator
+    ///
+    /// Only the right-side keys are buffered, and left items are passed
+    /// through without cloning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let users = vec![(1, "ann"), (2, "bob"), (3, "cid")];
+    /// let logins = vec![(1, "mon"), (3, "tue")];
+    ///
+    /// let result: Vec<_> = users
+    ///     .into_iter()
+    ///     .lob()
+    ///     .join_anti(logins, |u| u.0, |l| l.0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![(2, "bob")]);
+    /// ```
+    #[must_use]
+    pub fn join_anti<J, K, FL, FR>(
+        self,
+        other: J,
+        left_key: FL,
+        right_key: FR,
+    ) -> Lob<impl Iterator<Item = I::Item>>
+    where
+        J: IntoIterator,
+        K: Eq + Hash,
+        FL: Fn(&I::Item) -> K,
+        FR: Fn(&J::Item) -> K,
+    {
+        Lob::new(AntiJoinIterator::new(self.iter, other, left_key, right_key))
+    }
+
+    /// Cross join: pair every item with every item of another iterator
+    ///
+    /// The other side is buffered into a `Vec`. Pairs come in left-major
+    /// order: all pairs for the first left item, then the second, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = vec![1, 2]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .cross_join(["a", "b"])
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// ```
+    #[must_use]
+    pub fn cross_join<J>(self, other: J) -> Lob<impl Iterator<Item = (I::Item, J::Item)>>
+    where
+        I::Item: Clone,
+        J: IntoIterator,
+        J::Item: Clone,
+    {
+        Lob::new(CrossJoinIterator::new(self.iter, other))
+    }
+
+    // ========== Terminal Operations (consume iterator) ==========
+
+    /// Collect into a collection
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let result: Vec<_> = (0..5)
+    ///     .lob()
+    ///     .filter(|x| x % 2 == 0)
+    ///     .collect();
+    ///
+    /// assert_eq!(result, vec![0, 2, 4]);
+    /// ```
+    pub fn collect<B: FromIterator<I::Item>>(self) -> B {
+        self.iter.collect()
+    }
+
+    /// Count the number of elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let count = (0..10)
+    ///     .lob()
+    ///     .filter(|x| x % 2 == 0)
+    ///     .count();
+    ///
+    /// assert_eq!(count, 5);
+    /// ```
+    pub fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    /// Sum all elements
     ///
     /// # Examples
     ///
@@ -451,130 +1619,747 @@ impl<I: Iterator> Lob<I> {
     ///
     /// let sum = (1..=5).lob().sum::<i32>();
     ///
-    /// assert_eq!(sum, 15);
+    /// assert_eq!(sum, 15);
+    /// ```
+    pub fn sum<S>(self) -> S
+    where
+        S: std::iter::Sum<I::Item>,
+    {
+        self.iter.sum()
+    }
+
+    /// Find the minimum element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let min = vec![3, 1, 4, 1, 5].into_iter().lob().min();
+    ///
+    /// assert_eq!(min, Some(1));
+    /// ```
+    pub fn min(self) -> Option<I::Item>
+    where
+        I::Item: Ord,
+    {
+        self.iter.min()
+    }
+
+    /// Find the maximum element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let max = vec![3, 1, 4, 1, 5].into_iter().lob().max();
+    ///
+    /// assert_eq!(max, Some(5));
+    /// ```
+    pub fn max(self) -> Option<I::Item>
+    where
+        I::Item: Ord,
+    {
+        self.iter.max()
+    }
+
+    /// Find the minimum element using a comparator
+    ///
+    /// Works for items that are only `PartialOrd`, such as floats. If several
+    /// elements are equally minimal, the first is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let min = vec![2.5_f64, -1.0, 3.5]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .min_by(|a, b| a.total_cmp(b));
+    ///
+    /// assert_eq!(min, Some(-1.0));
+    /// ```
+    pub fn min_by<F>(self, compare: F) -> Option<I::Item>
+    where
+        F: FnMut(&I::Item, &I::Item) -> std::cmp::Ordering,
+    {
+        self.iter.min_by(compare)
+    }
+
+    /// Find the maximum element using a comparator
+    ///
+    /// Works for items that are only `PartialOrd`, such as floats. If several
+    /// elements are equally maximal, the last is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let max = vec![1.0, 3.5, 2.2]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .max_by(|a, b| a.partial_cmp(b).unwrap());
+    ///
+    /// assert_eq!(max, Some(3.5));
+    /// ```
+    pub fn max_by<F>(self, compare: F) -> Option<I::Item>
+    where
+        F: FnMut(&I::Item, &I::Item) -> std::cmp::Ordering,
+    {
+        self.iter.max_by(compare)
+    }
+
+    /// Get the first element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let first = (1..10).lob().first();
+    ///
+    /// assert_eq!(first, Some(1));
+    /// ```
+    pub fn first(mut self) -> Option<I::Item> {
+        self.iter.next()
+    }
+
+    /// Get the last element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let last = (1..10).lob().last();
+    ///
+    /// assert_eq!(last, Some(9));
+    /// ```
+    pub fn last(self) -> Option<I::Item> {
+        self.iter.last()
+    }
+
+    /// Reduce to a single value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let product = (1..=5).lob().reduce(|a, b| a * b);
+    ///
+    /// assert_eq!(product, Some(120));
+    /// ```
+    pub fn reduce<F>(self, f: F) -> Option<I::Item>
+    where
+        F: FnMut(I::Item, I::Item) -> I::Item,
+    {
+        self.iter.reduce(f)
+    }
+
+    /// Fold with an initial value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let sum = (1..=5).lob().fold(0, |a, b| a + b);
+    ///
+    /// assert_eq!(sum, 15);
+    /// ```
+    pub fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, I::Item) -> B,
+    {
+        self.iter.fold(init, f)
+    }
+
+    /// Fold with a fallible function, stopping at the first error
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `Err` produced by `f`; later elements are not visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let sum = (1..=5).lob().try_fold(0i32, |acc, x| acc.checked_add(x).ok_or("overflow"));
+    ///
+    /// assert_eq!(sum, Ok(15));
+    /// ```
+    pub fn try_fold<B, E, F>(mut self, init: B, f: F) -> Result<B, E>
+    where
+        F: FnMut(B, I::Item) -> Result<B, E>,
+    {
+        self.iter.try_fold(init, f)
+    }
+
+    /// Fold until the function asks to stop
+    ///
+    /// `f` returns `ControlFlow::Continue(acc)` to keep going or
+    /// `ControlFlow::Break(acc)` to stop early; either way the last
+    /// accumulator is returned. Elements after a break are not visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    /// use std::ops::ControlFlow;
+    ///
+    /// // Add up sizes until the budget of 10 is exceeded
+    /// let total = vec![4, 3, 5, 2].into_iter().lob().fold_while(0, |acc, x| {
+    ///     let acc = acc + x;
+    ///     if acc > 10 { ControlFlow::Break(acc) } else { ControlFlow::Continue(acc) }
+    /// });
+    ///
+    /// assert_eq!(total, 12);
+    /// ```
+    pub fn fold_while<B, F>(mut self, init: B, f: F) -> B
+    where
+        F: FnMut(B, I::Item) -> std::ops::ControlFlow<B, B>,
+    {
+        match self.iter.try_fold(init, f) {
+            std::ops::ControlFlow::Continue(acc) | std::ops::ControlFlow::Break(acc) => acc,
+        }
+    }
+
+    /// Collect into a Vec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let list = (0..5).lob().to_list();
+    ///
+    /// assert_eq!(list, vec![0, 1, 2, 3, 4]);
+    /// ```
+    pub fn to_list(self) -> Vec<I::Item> {
+        self.iter.collect()
+    }
+
+    /// Flatten one level of nesting and collect into a Vec
+    ///
+    /// Shorthand for `flatten().to_list()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let flat = vec![vec![1, 2], vec![3]].into_iter().lob().concat();
+    ///
+    /// assert_eq!(flat, vec![1, 2, 3]);
+    /// ```
+    pub fn concat<T>(self) -> Vec<T>
+    where
+        I::Item: IntoIterator<Item = T>,
+    {
+        self.iter.flatten().collect()
+    }
+
+    /// Collect a stream of `Result`s into a `Vec` of the successes
+    ///
+    /// Same as `collect::<Result<Vec<_>, _>>()` without the turbofish.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `Err` in the stream; later elements are not visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let parsed = vec!["1", "2", "3"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .map(|s| s.parse::<i32>())
+    ///     .collect_result();
+    ///
+    /// assert_eq!(parsed, Ok(vec![1, 2, 3]));
+    /// ```
+    pub fn collect_result<T, E>(self) -> Result<Vec<T>, E>
+    where
+        I: Iterator<Item = Result<T, E>>,
+    {
+        self.iter.collect()
+    }
+
+    /// Apply a fallible function to every element and collect the successes
+    ///
+    /// The eager counterpart of `map(f).collect_result()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `Err` produced by `f`; later elements are not visited.
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn sum<S>(self) -> S
+    /// use lob_core::LobExt;
+    ///
+    /// let parsed = vec!["1", "2", "x"].into_iter().lob().try_map(|s| s.parse::<i32>());
+    ///
+    /// assert!(parsed.is_err());
+    /// ```
+    pub fn try_map<F, B, E>(self, f: F) -> Result<Vec<B>, E>
     where
-        S: std::iter::Sum<I::Item>,
+        F: FnMut(I::Item) -> Result<B, E>,
     {
-        self.iter.sum()
+        self.iter.map(f).collect()
     }
 
-    /// Find the minimum element
+    /// Collect key-value pairs into a `HashMap`
+    ///
+    /// Later pairs overwrite earlier ones with the same key.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let min = vec![3, 1, 4, 1, 5].into_iter().lob().min();
+    /// let map = vec![(1, "a"), (2, "b")].into_iter().lob().to_map();
     ///
-    /// assert_eq!(min, Some(1));
+    /// assert_eq!(map[&1], "a");
+    /// assert_eq!(map[&2], "b");
     /// ```
-    pub fn min(self) -> Option<I::Item>
+    pub fn to_map<K, V>(self) -> HashMap<K, V>
     where
-        I::Item: Ord,
+        I: Iterator<Item = (K, V)>,
+        K: Eq + Hash,
     {
-        self.iter.min()
+        self.iter.collect()
     }
 
-    /// Find the maximum element
+    /// Collect into a `HashSet`
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let max = vec![3, 1, 4, 1, 5].into_iter().lob().max();
+    /// let set = vec![1, 1, 2].into_iter().lob().to_set();
     ///
-    /// assert_eq!(max, Some(5));
+    /// assert_eq!(set.len(), 2);
     /// ```
-    pub fn max(self) -> Option<I::Item>
+    pub fn to_set(self) -> HashSet<I::Item>
     where
-        I::Item: Ord,
+        I::Item: Eq + Hash,
     {
-        self.iter.max()
+        self.iter.collect()
     }
 
-    /// Get the first element
+    /// Concatenate string items with a separator
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let first = (1..10).lob().first();
+    /// let s = vec!["a", "b", "c"].into_iter().lob().join_str("-");
     ///
-    /// assert_eq!(first, Some(1));
+    /// assert_eq!(s, "a-b-c");
     /// ```
-    pub fn first(mut self) -> Option<I::Item> {
-        self.iter.next()
+    pub fn join_str(self, sep: &str) -> String
+    where
+        I::Item: AsRef<str>,
+    {
+        let mut out = String::new();
+        for (i, item) in self.iter.enumerate() {
+            if i > 0 {
+                out.push_str(sep);
+            }
+            out.push_str(item.as_ref());
+        }
+        out
     }
 
-    /// Get the last element
+    /// Compare against another stream as sets of lines
+    ///
+    /// Returns `(only_left, only_right, common)`. Duplicates are collapsed and
+    /// each bucket keeps the first-seen order of its source stream (`common`
+    /// follows the left side).
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let last = (1..10).lob().last();
+    /// let (only_a, only_b, both) = vec!["x", "y", "z"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .diff_lines(vec!["z", "w", "x"]);
     ///
-    /// assert_eq!(last, Some(9));
+    /// assert_eq!(only_a, vec!["y"]);
+    /// assert_eq!(only_b, vec!["w"]);
+    /// assert_eq!(both, vec!["x", "z"]);
     /// ```
-    pub fn last(self) -> Option<I::Item> {
-        self.iter.last()
+    #[allow(clippy::type_complexity)]
+    pub fn diff_lines<J>(self, other: J) -> (Vec<I::Item>, Vec<I::Item>, Vec<I::Item>)
+    where
+        I::Item: Eq + Hash + Clone,
+        J: IntoIterator<Item = I::Item>,
+    {
+        fn distinct<T: Eq + Hash + Clone>(items: impl Iterator<Item = T>) -> (Vec<T>, HashSet<T>) {
+            let mut seen = HashSet::new();
+            let ordered = items.filter(|item| seen.insert(item.clone())).collect();
+            (ordered, seen)
+        }
+
+        let (left, left_set) = distinct(self.iter);
+        let (right, right_set) = distinct(other.into_iter());
+
+        let (common, only_left) = left.into_iter().partition(|item| right_set.contains(item));
+        let only_right = right
+            .into_iter()
+            .filter(|item| !left_set.contains(item))
+            .collect();
+
+        (only_left, only_right, common)
     }
 
-    /// Reduce to a single value
+    /// Split a stream of pairs into two collections
+    ///
+    /// The inverse of [`zip`](Self::zip).
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let product = (1..=5).lob().reduce(|a, b| a * b);
+    /// let (nums, chars): (Vec<_>, Vec<_>) = vec![(1, 'a'), (2, 'b')]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .unzip();
     ///
-    /// assert_eq!(product, Some(120));
+    /// assert_eq!(nums, vec![1, 2]);
+    /// assert_eq!(chars, vec!['a', 'b']);
     /// ```
-    pub fn reduce<F>(self, f: F) -> Option<I::Item>
+    pub fn unzip<A, B, FromA, FromB>(self) -> (FromA, FromB)
     where
-        F: FnMut(I::Item, I::Item) -> I::Item,
+        I: Iterator<Item = (A, B)>,
+        FromA: Default + Extend<A>,
+        FromB: Default + Extend<B>,
     {
-        self.iter.reduce(f)
+        self.iter.unzip()
     }
 
-    /// Fold with an initial value
+    /// Collect records into a columnar [`ColumnBatch`](crate::ColumnBatch)
+    ///
+    /// Requires the `columnar` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::{ColumnValue, IntoRow, LobExt};
+    ///
+    /// struct Point { x: i64, y: i64 }
+    ///
+    /// impl IntoRow for Point {
+    ///     fn into_row(self) -> Vec<(String, ColumnValue)> {
+    ///         vec![("x".into(), self.x.into()), ("y".into(), self.y.into())]
+    ///     }
+    /// }
+    ///
+    /// let batch = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .collect_columns();
+    ///
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.column("x").unwrap(), &[ColumnValue::Int(1), ColumnValue::Int(3)]);
+    /// ```
+    #[cfg(feature = "columnar")]
+    pub fn collect_columns(self) -> crate::ColumnBatch
+    where
+        I::Item: crate::IntoRow,
+    {
+        self.iter.collect()
+    }
+
+    /// Arithmetic mean, or `None` for an empty stream
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let sum = (1..=5).lob().fold(0, |a, b| a + b);
+    /// let mean = vec![1, 2, 3, 4].into_iter().lob().mean();
     ///
-    /// assert_eq!(sum, 15);
+    /// assert_eq!(mean, Some(2.5));
     /// ```
-    pub fn fold<B, F>(self, init: B, f: F) -> B
+    pub fn mean(self) -> Option<f64>
     where
-        F: FnMut(B, I::Item) -> B,
+        I::Item: Into<f64>,
     {
-        self.iter.fold(init, f)
+        Welford::from_values(self.iter.map(Into::into)).mean()
     }
 
-    /// Collect into a Vec
+    /// Population variance, or `None` for an empty stream
+    ///
+    /// Computed in a single pass with Welford's algorithm, which avoids
+    /// buffering and the cancellation error of the naive sum-of-squares form.
     ///
     /// # Examples
     ///
     /// ```
     /// use lob_core::LobExt;
     ///
-    /// let list = (0..5).lob().to_list();
+    /// let var = vec![2, 4, 4, 4, 5, 5, 7, 9].into_iter().lob().variance();
     ///
-    /// assert_eq!(list, vec![0, 1, 2, 3, 4]);
+    /// assert_eq!(var, Some(4.0));
     /// ```
-    pub fn to_list(self) -> Vec<I::Item> {
-        self.iter.collect()
+    pub fn variance(self) -> Option<f64>
+    where
+        I::Item: Into<f64>,
+    {
+        Welford::from_values(self.iter.map(Into::into)).variance()
+    }
+
+    /// Population standard deviation, or `None` for an empty stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let sd = vec![2, 4, 4, 4, 5, 5, 7, 9].into_iter().lob().stddev();
+    ///
+    /// assert_eq!(sd, Some(2.0));
+    /// ```
+    pub fn stddev(self) -> Option<f64>
+    where
+        I::Item: Into<f64>,
+    {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Element at quantile `p` in `[0.0, 1.0]` using the nearest-rank method
+    ///
+    /// Buffers and sorts the stream, then returns the smallest element with at
+    /// least `p` of the data at or below it (no interpolation, so the result is
+    /// always an actual element). Returns `None` for an empty stream or when
+    /// `p` is outside `[0.0, 1.0]` or NaN. Incomparable values such as NaN
+    /// are treated as equal when sorting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let p90 = (1..=100).lob().percentile(0.9);
+    ///
+    /// assert_eq!(p90, Some(90));
+    /// ```
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn percentile(self, p: f64) -> Option<I::Item>
+    where
+        I::Item: PartialOrd,
+    {
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+
+        let mut items: Vec<_> = self.iter.collect();
+        if items.is_empty() {
+            return None;
+        }
+        items.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Rounding in `p * n` can land just above an integer (0.07 * 100 is
+        // 7.000000000000001); allow for that error before taking the ceiling
+        let n = items.len() as f64;
+        let rank = ((p - f64::EPSILON) * n).ceil() as usize;
+        Some(items.swap_remove(rank.saturating_sub(1)))
+    }
+
+    /// Median element (nearest-rank, so the lower middle for even counts)
+    ///
+    /// Equivalent to `percentile(0.5)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// assert_eq!(vec![3, 1, 2].into_iter().lob().median(), Some(2));
+    /// assert_eq!(vec![1, 2, 3, 4].into_iter().lob().median(), Some(2));
+    /// ```
+    pub fn median(self) -> Option<I::Item>
+    where
+        I::Item: PartialOrd,
+    {
+        self.percentile(0.5)
+    }
+
+    /// Uniform random sample of up to `k` elements
+    ///
+    /// Single pass (Algorithm R) holding at most `k` items, so it works on
+    /// streams far larger than memory. If the stream has `k` or fewer
+    /// elements, all of them are returned in order. The sample differs from
+    /// run to run; use [`reservoir_sample_seeded`](Self::reservoir_sample_seeded)
+    /// for reproducible output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let sample = (0..1_000).lob().reservoir_sample(10);
+    ///
+    /// assert_eq!(sample.len(), 10);
+    /// assert!(sample.iter().all(|x| (0..1_000).contains(x)));
+    /// ```
+    pub fn reservoir_sample(self, k: usize) -> Vec<I::Item> {
+        self.reservoir_sample_with(k, &mut SplitMix64::from_entropy())
+    }
+
+    /// Uniform random sample of up to `k` elements from a fixed seed
+    ///
+    /// Like [`reservoir_sample`](Self::reservoir_sample), but the same seed
+    /// and input always produce the same sample.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let a = (0..1_000).lob().reservoir_sample_seeded(5, 42);
+    /// let b = (0..1_000).lob().reservoir_sample_seeded(5, 42);
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn reservoir_sample_seeded(self, k: usize, seed: u64) -> Vec<I::Item> {
+        self.reservoir_sample_with(k, &mut SplitMix64::new(seed))
+    }
+
+    fn reservoir_sample_with(self, k: usize, rng: &mut SplitMix64) -> Vec<I::Item> {
+        let mut reservoir = Vec::with_capacity(k);
+        if k == 0 {
+            return reservoir;
+        }
+        for (i, item) in self.iter.enumerate() {
+            if i < k {
+                reservoir.push(item);
+            } else {
+                // Keep the (i + 1)th item with probability k / (i + 1)
+                let j = rng.below(i + 1);
+                if j < k {
+                    reservoir[j] = item;
+                }
+            }
+        }
+        reservoir
+    }
+
+    /// Aggregate each group in a single pass, SQL `GROUP BY` style
+    ///
+    /// Every key's accumulator starts from `init()` and each item is folded
+    /// into it with `reduce`. Unlike [`group_by`](Self::group_by) followed by a
+    /// map, the items of a group are never stored, so memory grows with the
+    /// number of keys rather than the number of items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let totals = vec![("a", 1), ("b", 2), ("a", 3)]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .group_reduce(|x| x.0, || 0, |acc, x| *acc += x.1);
+    ///
+    /// assert_eq!(totals["a"], 4);
+    /// assert_eq!(totals["b"], 2);
+    /// ```
+    pub fn group_reduce<K, B, FK, FI, FR>(
+        self,
+        mut key_fn: FK,
+        mut init: FI,
+        mut reduce: FR,
+    ) -> HashMap<K, B>
+    where
+        K: Eq + Hash,
+        FK: FnMut(&I::Item) -> K,
+        FI: FnMut() -> B,
+        FR: FnMut(&mut B, I::Item),
+    {
+        let mut groups: HashMap<K, B> = HashMap::new();
+        for item in self.iter {
+            let acc = groups.entry(key_fn(&item)).or_insert_with(&mut init);
+            reduce(acc, item);
+        }
+        groups
+    }
+
+    /// Sum a derived value per key in a single pass
+    ///
+    /// Shorthand for the most common [`group_reduce`](Self::group_reduce):
+    /// bytes per host, requests per status, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let bytes = vec![("web1", 512), ("web2", 128), ("web1", 64)]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .sum_by_key(|r| r.0, |r| r.1);
+    ///
+    /// assert_eq!(bytes["web1"], 576);
+    /// assert_eq!(bytes["web2"], 128);
+    /// ```
+    pub fn sum_by_key<K, V, FK, FV>(self, key_fn: FK, mut val_fn: FV) -> HashMap<K, V>
+    where
+        K: Eq + Hash,
+        V: std::iter::Sum + std::ops::AddAssign,
+        FK: FnMut(&I::Item) -> K,
+        FV: FnMut(I::Item) -> V,
+    {
+        self.group_reduce(
+            key_fn,
+            || std::iter::empty::<V>().sum(),
+            |acc, item| *acc += val_fn(item),
+        )
+    }
+
+    /// Group elements by key into a `HashMap`
+    ///
+    /// Each bucket keeps its items in input order. Use
+    /// [`group_by`](Self::group_by) to stream `(key, items)` pairs in
+    /// first-seen key order instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let groups = vec!["apple", "avocado", "banana"]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .group_into_map(|s| s.chars().next());
+    ///
+    /// assert_eq!(groups[&Some('a')], vec!["apple", "avocado"]);
+    /// assert_eq!(groups[&Some('b')], vec!["banana"]);
+    /// ```
+    pub fn group_into_map<K, F>(self, key_fn: F) -> HashMap<K, Vec<I::Item>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&I::Item) -> K,
+    {
+        self.group_reduce(key_fn, Vec::new, Vec::push)
     }
 
     /// Check if any element matches a predicate
@@ -595,6 +2380,69 @@ impl<I: Iterator> Lob<I> {
         self.iter.any(f)
     }
 
+    /// Count elements by two keys into a nested frequency map
+    ///
+    /// Useful for hierarchical counts such as `country -> city -> count`,
+    /// built in a single pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let counts = vec![("us", "nyc"), ("us", "la"), ("us", "nyc"), ("fr", "paris")]
+    ///     .into_iter()
+    ///     .lob()
+    ///     .nested_counts(|x| x.0, |x| x.1);
+    ///
+    /// assert_eq!(counts["us"]["nyc"], 2);
+    /// assert_eq!(counts["fr"]["paris"], 1);
+    /// ```
+    pub fn nested_counts<K1, K2, F1, F2>(
+        self,
+        mut f1: F1,
+        mut f2: F2,
+    ) -> HashMap<K1, HashMap<K2, usize>>
+    where
+        K1: Eq + Hash,
+        K2: Eq + Hash,
+        F1: FnMut(&I::Item) -> K1,
+        F2: FnMut(&I::Item) -> K2,
+    {
+        let mut counts: HashMap<K1, HashMap<K2, usize>> = HashMap::new();
+        for item in self.iter {
+            *counts
+                .entry(f1(&item))
+                .or_default()
+                .entry(f2(&item))
+                .or_default() += 1;
+        }
+        counts
+    }
+
+    /// Count occurrences of each n-gram of size n
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let counts = vec!["a", "b", "a", "b"].into_iter().lob().ngram_counts(2);
+    ///
+    /// assert_eq!(counts[&vec!["a", "b"]], 2);
+    /// assert_eq!(counts[&vec!["b", "a"]], 1);
+    /// ```
+    pub fn ngram_counts(self, n: usize) -> HashMap<Vec<I::Item>, usize>
+    where
+        I::Item: Clone + Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+        for gram in WindowIterator::new(self.iter, n) {
+            *counts.entry(gram).or_default() += 1;
+        }
+        counts
+    }
+
     /// Check if all elements match a predicate
     ///
     /// # Examples
@@ -612,6 +2460,41 @@ impl<I: Iterator> Lob<I> {
     {
         self.iter.all(f)
     }
+
+    /// Return the first non-`None` result of `f`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// let n = vec!["x", "12", "y"].into_iter().lob().find_map(|s| s.parse::<i32>().ok());
+    ///
+    /// assert_eq!(n, Some(12));
+    /// ```
+    pub fn find_map<F, B>(mut self, f: F) -> Option<B>
+    where
+        F: FnMut(I::Item) -> Option<B>,
+    {
+        self.iter.find_map(f)
+    }
+
+    /// Index of the first element equal to `target`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_core::LobExt;
+    ///
+    /// assert_eq!(vec![5, 7, 9].into_iter().lob().index_of(&7), Some(1));
+    /// assert_eq!(vec![5, 7, 9].into_iter().lob().index_of(&8), None);
+    /// ```
+    pub fn index_of(mut self, target: &I::Item) -> Option<usize>
+    where
+        I::Item: PartialEq,
+    {
+        self.iter.position(|item| item == *target)
+    }
 }
 
 /// Extension trait to add `.lob()` method to all iterators
@@ -638,12 +2521,22 @@ pub trait LobExt: Iterator + Sized {
 
 impl<I: Iterator> LobExt for I {}
 
-/// Implement `IntoIterator` for Lob to allow using it in for loops
-impl<I: Iterator> IntoIterator for Lob<I> {
+/// `Lob` is itself an iterator, so it works in for loops and with any
+/// `Iterator`-based API; `size_hint` is forwarded from the wrapped iterator
+impl<I: Iterator> Iterator for Lob<I> {
     type Item = I::Item;
-    type IntoIter = I;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Lob<I> {
+    fn len(&self) -> usize {
+        self.iter.len()
     }
 }
@@ -1,4 +1,4 @@
-//! Grouping iterators: `chunk`, `window`, `group_by`
+//! Grouping iterators: `chunk`, `chunk_step`, `chunk_bytes`, `window`, `pairs`, `rolling`, `group_by`, `group_consecutive`, `batching`
 
 #![allow(clippy::missing_const_for_fn)]
 
@@ -39,10 +39,209 @@ impl<I: Iterator> Iterator for ChunkIterator<I> {
     }
 }
 
-/// Iterator that creates sliding windows of size n
+/// Iterator that groups elements into chunks of `size`, starting a new
+/// chunk every `step` elements
+pub struct StepChunkIterator<I: Iterator> {
+    iter: I,
+    size: usize,
+    step: usize,
+    buffer: VecDeque<I::Item>,
+    /// Elements to discard before the next chunk when `step > size`
+    pending_skip: usize,
+    done: bool,
+}
+
+impl<I: Iterator> StepChunkIterator<I>
+where
+    I::Item: Clone,
+{
+    pub fn new(iter: I, size: usize, step: usize) -> Self {
+        assert!(size > 0, "chunk size must be greater than 0");
+        assert!(step > 0, "chunk step must be greater than 0");
+        Self {
+            iter,
+            size,
+            step,
+            buffer: VecDeque::with_capacity(size),
+            pending_skip: 0,
+            done: false,
+        }
+    }
+
+    fn pull(&mut self) -> Option<I::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next();
+        self.done = item.is_none();
+        item
+    }
+}
+
+impl<I: Iterator> Iterator for StepChunkIterator<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip the gap between chunks
+        while self.pending_skip > 0 {
+            self.pull()?;
+            self.pending_skip -= 1;
+        }
+
+        // Fill up the chunk, tracking whether it holds anything not yet emitted
+        let mut has_new = false;
+        while self.buffer.len() < self.size {
+            match self.pull() {
+                Some(item) => {
+                    self.buffer.push_back(item);
+                    has_new = true;
+                }
+                None => break,
+            }
+        }
+
+        // A trailing chunk made only of overlap with the previous one is dropped
+        if !has_new {
+            return None;
+        }
+
+        let chunk: Vec<I::Item> = self.buffer.iter().cloned().collect();
+
+        if self.step >= self.size {
+            self.buffer.clear();
+            self.pending_skip = self.step - self.size;
+        } else {
+            self.buffer.drain(..self.step.min(self.buffer.len()));
+        }
+
+        Some(chunk)
+    }
+}
+
+/// Iterator that groups strings into chunks of at most `max_bytes` total
+pub struct ByteChunkIterator<I: Iterator> {
+    iter: I,
+    max_bytes: usize,
+    /// Item that did not fit in the previous chunk
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator> ByteChunkIterator<I> {
+    pub fn new(iter: I, max_bytes: usize) -> Self {
+        Self {
+            iter,
+            max_bytes,
+            pending: None,
+        }
+    }
+}
+
+impl<I> Iterator for ByteChunkIterator<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The first item always starts a chunk, even if it is oversized
+        let first = self.pending.take().or_else(|| self.iter.next())?;
+        let mut bytes = first.as_ref().len();
+        let mut chunk = vec![first];
+
+        for item in self.iter.by_ref() {
+            let len = item.as_ref().len();
+            if bytes + len > self.max_bytes {
+                self.pending = Some(item);
+                break;
+            }
+            bytes += len;
+            chunk.push(item);
+        }
+
+        Some(chunk)
+    }
+}
+
+/// Iterator over adjacent pairs: `(a, b), (b, c), ...`
+pub struct PairsIterator<I: Iterator> {
+    iter: I,
+    prev: Option<I::Item>,
+}
+
+impl<I: Iterator> PairsIterator<I> {
+    pub fn new(iter: I) -> Self {
+        Self { iter, prev: None }
+    }
+}
+
+impl<I: Iterator> Iterator for PairsIterator<I>
+where
+    I::Item: Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prev = match self.prev.take() {
+            Some(prev) => prev,
+            None => self.iter.next()?,
+        };
+        let next = self.iter.next()?;
+        // Each item is cloned once, to serve as the left side of the next pair
+        self.prev = Some(next.clone());
+        Some((prev, next))
+    }
+}
+
+/// Iterator that collapses runs of equal adjacent items into `(run_length, item)`
+pub struct DedupCountsIterator<I: Iterator> {
+    iter: I,
+    // First item of the next run, read while scanning the previous one
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator> DedupCountsIterator<I> {
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter,
+            pending: None,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for DedupCountsIterator<I>
+where
+    I::Item: PartialEq,
+{
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.pending.take() {
+            Some(item) => item,
+            None => self.iter.next()?,
+        };
+        let mut count = 1;
+        for item in self.iter.by_ref() {
+            if item == current {
+                count += 1;
+            } else {
+                self.pending = Some(item);
+                break;
+            }
+        }
+        Some((count, current))
+    }
+}
+
+/// Iterator that creates sliding windows of size n, advancing `step`
+/// elements between windows
 pub struct WindowIterator<I: Iterator> {
     iter: I,
     window_size: usize,
+    step: usize,
     buffer: VecDeque<I::Item>,
     started: bool,
 }
@@ -52,10 +251,16 @@ where
     I::Item: Clone,
 {
     pub fn new(iter: I, window_size: usize) -> Self {
+        Self::with_step(iter, window_size, 1)
+    }
+
+    pub fn with_step(iter: I, window_size: usize, step: usize) -> Self {
         assert!(window_size > 0, "window size must be greater than 0");
+        assert!(step > 0, "window step must be greater than 0");
         Self {
             iter,
             window_size,
+            step,
             buffer: VecDeque::with_capacity(window_size),
             started: false,
         }
@@ -85,26 +290,160 @@ where
             return None;
         }
 
-        // Slide window: remove first, add new
-        match self.iter.next() {
-            Some(item) => {
-                self.buffer.pop_front();
-                self.buffer.push_back(item);
-                Some(self.buffer.iter().cloned().collect())
+        // Slide window: drop `step` items (skipping any gap past the
+        // window), then refill
+        if self.step > self.window_size {
+            self.buffer.clear();
+            for _ in 0..self.step - self.window_size {
+                self.iter.next()?;
             }
-            None => None,
+        } else {
+            self.buffer.drain(..self.step);
+        }
+        while self.buffer.len() < self.window_size {
+            self.buffer.push_back(self.iter.next()?);
+        }
+        Some(self.buffer.iter().cloned().collect())
+    }
+}
+
+/// Iterator that applies a function to each sliding window of size n
+///
+/// The window is passed by reference, so no per-window `Vec` is built.
+pub struct WindowReduceIterator<I: Iterator, F> {
+    iter: I,
+    window_size: usize,
+    buffer: VecDeque<I::Item>,
+    f: F,
+}
+
+impl<I: Iterator, B, F> WindowReduceIterator<I, F>
+where
+    F: FnMut(&VecDeque<I::Item>) -> B,
+{
+    pub fn new(iter: I, window_size: usize, f: F) -> Self {
+        assert!(window_size > 0, "window size must be greater than 0");
+        Self {
+            iter,
+            window_size,
+            buffer: VecDeque::with_capacity(window_size),
+            f,
+        }
+    }
+}
+
+impl<I: Iterator, B, F> Iterator for WindowReduceIterator<I, F>
+where
+    F: FnMut(&VecDeque<I::Item>) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.len() == self.window_size {
+            self.buffer.pop_front();
+        }
+        while self.buffer.len() < self.window_size {
+            self.buffer.push_back(self.iter.next()?);
+        }
+        Some((self.f)(&self.buffer))
+    }
+}
+
+/// Iterator that applies a function to each sliding window as a slice
+///
+/// Items are kept in a buffer of twice the window size; once it fills up the
+/// last `window - 1` items are shifted to the front, so every window is
+/// contiguous and each item is moved at most once per `window` steps.
+pub struct RollingIterator<I: Iterator, F> {
+    iter: I,
+    window_size: usize,
+    buffer: Vec<I::Item>,
+    f: F,
+}
+
+impl<I: Iterator, B, F> RollingIterator<I, F>
+where
+    F: FnMut(&[I::Item]) -> B,
+{
+    pub fn new(iter: I, window_size: usize, f: F) -> Self {
+        assert!(window_size > 0, "window size must be greater than 0");
+        Self {
+            iter,
+            window_size,
+            buffer: Vec::with_capacity(window_size * 2),
+            f,
+        }
+    }
+}
+
+impl<I: Iterator, B, F> Iterator for RollingIterator<I, F>
+where
+    F: FnMut(&[I::Item]) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.len() == self.window_size * 2 {
+            self.buffer.drain(..=self.window_size);
+        }
+        let item = self.iter.next()?;
+        self.buffer.push(item);
+        while self.buffer.len() < self.window_size {
+            self.buffer.push(self.iter.next()?);
+        }
+
+        let start = self.buffer.len() - self.window_size;
+        Some((self.f)(&self.buffer[start..]))
+    }
+}
+
+/// Iterator that creates sliding windows as fixed-size arrays
+pub struct ArrayWindowIterator<I: Iterator, const N: usize> {
+    iter: I,
+    buffer: VecDeque<I::Item>,
+}
+
+impl<I: Iterator, const N: usize> ArrayWindowIterator<I, N>
+where
+    I::Item: Clone,
+{
+    pub fn new(iter: I) -> Self {
+        assert!(N > 0, "window size must be greater than 0");
+        Self {
+            iter,
+            buffer: VecDeque::with_capacity(N),
         }
     }
 }
 
+impl<I: Iterator, const N: usize> Iterator for ArrayWindowIterator<I, N>
+where
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Fill on first call, then slide by one
+        while self.buffer.len() < N {
+            self.buffer.push_back(self.iter.next()?);
+        }
+
+        let window = std::array::from_fn(|i| self.buffer[i].clone());
+        self.buffer.pop_front();
+        Some(window)
+    }
+}
+
 /// Specialized `group_by` that returns all groups at once
+///
+/// Groups are emitted in the order their keys were first seen.
 pub struct GroupByCollectIterator<I, K, F>
 where
     I: Iterator,
     K: Eq + Hash,
     F: FnMut(&I::Item) -> K,
 {
-    groups: Option<std::collections::hash_map::IntoIter<K, Vec<I::Item>>>,
+    groups: Option<std::vec::IntoIter<(K, Vec<I::Item>)>>,
     iter: Option<I>,
     key_fn: Option<F>,
 }
@@ -135,15 +474,35 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         // Lazy initialization: collect groups on first call
         if self.groups.is_none() {
-            let mut groups: HashMap<K, Vec<I::Item>> = HashMap::new();
+            // Map each key to the position of its group in first-seen order
+            let mut index: HashMap<K, usize> = HashMap::new();
+            let mut buckets: Vec<Vec<I::Item>> = Vec::new();
             let mut key_fn = self.key_fn.take().expect("key_fn should be Some");
             let iter = self.iter.take().expect("iter should be Some");
 
             for item in iter {
                 let key = key_fn(&item);
-                groups.entry(key).or_default().push(item);
+                let idx = *index.entry(key).or_insert_with(|| {
+                    buckets.push(Vec::new());
+                    buckets.len() - 1
+                });
+                buckets[idx].push(item);
             }
 
+            // Move the keys out of the index into their group positions
+            let mut keys: Vec<Option<K>> = std::iter::repeat_with(|| None)
+                .take(buckets.len())
+                .collect();
+            for (key, idx) in index {
+                keys[idx] = Some(key);
+            }
+
+            let groups: Vec<(K, Vec<I::Item>)> = keys
+                .into_iter()
+                .map(|key| key.expect("every group has a key"))
+                .zip(buckets)
+                .collect();
+
             self.groups = Some(groups.into_iter());
         }
 
@@ -151,3 +510,91 @@ where
         self.groups.as_mut().and_then(std::iter::Iterator::next)
     }
 }
+
+/// Iterator that groups runs of adjacent elements sharing a key
+///
+/// Each group is emitted as soon as the key changes, so only one group is
+/// buffered at a time.
+pub struct ConsecutiveGroupIterator<I, K, F>
+where
+    I: Iterator,
+{
+    iter: I,
+    key_fn: F,
+    /// First element of the next group, read while closing the previous one
+    pending: Option<(K, I::Item)>,
+}
+
+impl<I, K, F> ConsecutiveGroupIterator<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    pub fn new(iter: I, key_fn: F) -> Self {
+        Self {
+            iter,
+            key_fn,
+            pending: None,
+        }
+    }
+}
+
+impl<I, K, F> Iterator for ConsecutiveGroupIterator<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, first) = if let Some(pending) = self.pending.take() {
+            pending
+        } else {
+            let item = self.iter.next()?;
+            ((self.key_fn)(&item), item)
+        };
+
+        let mut group = vec![first];
+        for item in self.iter.by_ref() {
+            let next_key = (self.key_fn)(&item);
+            if next_key == key {
+                group.push(item);
+            } else {
+                self.pending = Some((next_key, item));
+                break;
+            }
+        }
+
+        Some((key, group))
+    }
+}
+
+/// Iterator that builds each output by letting a closure pull from the source
+pub struct BatchingIterator<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I, B, F> BatchingIterator<I, F>
+where
+    I: Iterator,
+    F: FnMut(&mut I) -> Option<B>,
+{
+    pub fn new(iter: I, f: F) -> Self {
+        Self { iter, f }
+    }
+}
+
+impl<I, B, F> Iterator for BatchingIterator<I, F>
+where
+    I: Iterator,
+    F: FnMut(&mut I) -> Option<B>,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.f)(&mut self.iter)
+    }
+}
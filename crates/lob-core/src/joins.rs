@@ -1,9 +1,13 @@
-//! Join operations: inner join, left join
+//! Join operations: inner join, left join, anti join, cross join
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
 /// Inner join iterator
+///
+/// The right side is grouped by key once up front. Each left item is looked
+/// up a single time and then paired with every item in its matched group,
+/// cloning the left item only for the pairs before the last one.
 pub struct InnerJoinIterator<I, J, K, FL, FR>
 where
     I: Iterator,
@@ -13,9 +17,11 @@ where
     FR: Fn(&J::Item) -> K,
 {
     left: I,
-    right_map: HashMap<K, Vec<J::Item>>,
+    right_index: HashMap<K, usize>,
+    right_groups: Vec<Vec<J::Item>>,
     left_key: FL,
-    current_left: Option<I::Item>,
+    /// Current left item and the index of its matched right group
+    current: Option<(I::Item, usize)>,
     current_right_idx: usize,
     _right_key: std::marker::PhantomData<FR>,
 }
@@ -30,18 +36,25 @@ where
     FR: Fn(&J::Item) -> K,
 {
     pub fn new(left: I, right: J, left_key: FL, right_key: FR) -> Self {
-        // Build hash map from right side
-        let mut right_map: HashMap<K, Vec<J::Item>> = HashMap::new();
+        // Group right side by key, keeping groups in a Vec so the iterator
+        // can refer to a group by index without re-hashing per match
+        let mut right_index: HashMap<K, usize> = HashMap::new();
+        let mut right_groups: Vec<Vec<J::Item>> = Vec::new();
         for item in right {
             let key = right_key(&item);
-            right_map.entry(key).or_default().push(item);
+            let idx = *right_index.entry(key).or_insert_with(|| {
+                right_groups.push(Vec::new());
+                right_groups.len() - 1
+            });
+            right_groups[idx].push(item);
         }
 
         Self {
             left,
-            right_map,
+            right_index,
+            right_groups,
             left_key,
-            current_left: None,
+            current: None,
             current_right_idx: 0,
             _right_key: std::marker::PhantomData,
         }
@@ -62,39 +75,29 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            // If we have a current left item, try to pair it with right items
-            if let Some(left_item) = &self.current_left {
-                let key = (self.left_key)(left_item);
+            // Pair the current left item with the next item in its group
+            if let Some((left_item, group_idx)) = &self.current {
+                let group = &self.right_groups[*group_idx];
+                let idx = self.current_right_idx;
+                let right_item = group[idx].clone();
+                self.current_right_idx += 1;
 
-                if let Some(right_items) = self.right_map.get(&key) {
-                    if self.current_right_idx < right_items.len() {
-                        let result = (
-                            self.current_left.take().unwrap(),
-                            right_items[self.current_right_idx].clone(),
-                        );
-                        self.current_right_idx += 1;
-
-                        // Re-borrow left item if more right items remain
-                        if self.current_right_idx < right_items.len() {
-                            self.current_left = Some(result.0.clone());
-                        }
-
-                        return Some(result);
-                    }
-                }
+                // Move the left item out on its last match instead of cloning
+                let left_item = if self.current_right_idx < group.len() {
+                    left_item.clone()
+                } else {
+                    self.current.take().map(|(item, _)| item)?
+                };
 
-                // No (more) matches for current left item, move to next
-                self.current_left = None;
-                self.current_right_idx = 0;
+                return Some((left_item, right_item));
             }
 
-            // Get next left item
-            match self.left.next() {
-                Some(left_item) => {
-                    self.current_left = Some(left_item);
-                    self.current_right_idx = 0;
-                }
-                None => return None,
+            // Advance to the next left item that has at least one match
+            let left_item = self.left.next()?;
+            let key = (self.left_key)(&left_item);
+            if let Some(&group_idx) = self.right_index.get(&key) {
+                self.current = Some((left_item, group_idx));
+                self.current_right_idx = 0;
             }
         }
     }
@@ -207,3 +210,116 @@ where
         }
     }
 }
+
+/// Anti join iterator: yields left items whose key has no match on the right
+pub struct AntiJoinIterator<I, K, FL>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    FL: Fn(&I::Item) -> K,
+{
+    left: I,
+    right_keys: HashSet<K>,
+    left_key: FL,
+}
+
+impl<I, K, FL> AntiJoinIterator<I, K, FL>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    FL: Fn(&I::Item) -> K,
+{
+    pub fn new<J, FR>(left: I, right: J, left_key: FL, right_key: FR) -> Self
+    where
+        J: IntoIterator,
+        FR: Fn(&J::Item) -> K,
+    {
+        // Only the right keys are needed, not the items themselves
+        let right_keys: HashSet<K> = right.into_iter().map(|item| right_key(&item)).collect();
+
+        Self {
+            left,
+            right_keys,
+            left_key,
+        }
+    }
+}
+
+impl<I, K, FL> Iterator for AntiJoinIterator<I, K, FL>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    FL: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let right_keys = &self.right_keys;
+        let left_key = &self.left_key;
+        self.left
+            .by_ref()
+            .find(|item| !right_keys.contains(&left_key(item)))
+    }
+}
+
+/// Cross join iterator: yields every (left, right) pair
+///
+/// The right side is buffered into a `Vec` once up front; pairs are produced
+/// in left-major order. The left item is cloned for every pair but the last.
+pub struct CrossJoinIterator<I, R>
+where
+    I: Iterator,
+{
+    left: I,
+    right: Vec<R>,
+    current: Option<I::Item>,
+    current_right_idx: usize,
+}
+
+impl<I, R> CrossJoinIterator<I, R>
+where
+    I: Iterator,
+{
+    pub fn new<J>(left: I, right: J) -> Self
+    where
+        J: IntoIterator<Item = R>,
+    {
+        Self {
+            left,
+            right: right.into_iter().collect(),
+            current: None,
+            current_right_idx: 0,
+        }
+    }
+}
+
+impl<I, R> Iterator for CrossJoinIterator<I, R>
+where
+    I: Iterator,
+    I::Item: Clone,
+    R: Clone,
+{
+    type Item = (I::Item, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.right.is_empty() {
+            return None;
+        }
+        if self.current.is_none() {
+            self.current = Some(self.left.next()?);
+            self.current_right_idx = 0;
+        }
+
+        let right_item = self.right[self.current_right_idx].clone();
+        self.current_right_idx += 1;
+
+        // Move the left item out on its last pair instead of cloning
+        let left_item = if self.current_right_idx < self.right.len() {
+            self.current.clone()?
+        } else {
+            self.current.take()?
+        };
+
+        Some((left_item, right_item))
+    }
+}
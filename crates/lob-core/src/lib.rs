@@ -6,11 +6,21 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "columnar")]
+mod columnar;
 mod fluent;
 mod grouping;
 mod joins;
+mod random;
+mod selection;
+mod statistics;
+mod transformation;
 
 pub use fluent::{Lob, LobExt};
+pub use transformation::Position;
+
+#[cfg(feature = "columnar")]
+pub use columnar::{ColumnBatch, ColumnValue, IntoRow};
 
 // Re-export commonly used types
 pub use std::collections::{HashMap, HashSet};
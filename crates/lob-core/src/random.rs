@@ -0,0 +1,46 @@
+//! Small internal PRNG for sampling and shuffling
+//!
+//! `SplitMix64` is fast, has a 64-bit state and passes `BigCrush`, which is
+//! plenty for sampling data; it is not cryptographically secure.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// `SplitMix64` pseudo-random number generator
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a generator with a fixed seed (reproducible output)
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Create a generator seeded from the process's hash randomness
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(0x5eed);
+        Self::new(hasher.finish())
+    }
+
+    /// Next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..n` (`n` must be non-zero)
+    ///
+    /// Uses the multiply-shift reduction; the bias is at most `n / 2^64`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, n: usize) -> usize {
+        debug_assert!(n > 0, "range must be non-empty");
+        let wide = u128::from(self.next_u64()) * n as u128;
+        // The high word is < n, so it fits in usize
+        (wide >> 64) as usize
+    }
+}
@@ -0,0 +1,137 @@
+//! Selection iterators: `take_matching`, `skip_last`, `dedup_by_key`
+
+#![allow(clippy::missing_const_for_fn)]
+
+use std::collections::VecDeque;
+
+/// Iterator that yields elements until n of them have matched a predicate
+pub struct TakeMatchingIterator<I, F> {
+    iter: I,
+    remaining: usize,
+    predicate: F,
+}
+
+impl<I, F> TakeMatchingIterator<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> bool,
+{
+    pub fn new(iter: I, n: usize, predicate: F) -> Self {
+        Self {
+            iter,
+            remaining: n,
+            predicate,
+        }
+    }
+}
+
+impl<I, F> Iterator for TakeMatchingIterator<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Stop before pulling from the source once enough matches were seen
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let item = self.iter.next()?;
+        if (self.predicate)(&item) {
+            self.remaining -= 1;
+        }
+        Some(item)
+    }
+}
+
+/// Iterator that yields all but the last n elements
+///
+/// Keeps an n-slot delay buffer, so an element is released only once n newer
+/// elements have been seen.
+pub struct SkipLastIterator<I: Iterator> {
+    iter: I,
+    n: usize,
+    buffer: VecDeque<I::Item>,
+}
+
+impl<I: Iterator> SkipLastIterator<I> {
+    pub fn new(iter: I, n: usize) -> Self {
+        Self {
+            iter,
+            n,
+            buffer: VecDeque::with_capacity(n),
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for SkipLastIterator<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() < self.n {
+            self.buffer.push_back(self.iter.next()?);
+        }
+
+        let item = self.iter.next()?;
+        if self.n == 0 {
+            return Some(item);
+        }
+        self.buffer.push_back(item);
+        self.buffer.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let held = self.buffer.len();
+        let pending = self.n - held;
+        (
+            lower.saturating_sub(pending),
+            upper.map(|u| u.saturating_sub(pending)),
+        )
+    }
+}
+
+/// Iterator that drops items whose key equals the previous item's key
+pub struct DedupByKeyIterator<I, K, F> {
+    iter: I,
+    key_fn: F,
+    last_key: Option<K>,
+}
+
+impl<I, K, F> DedupByKeyIterator<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    pub fn new(iter: I, key_fn: F) -> Self {
+        Self {
+            iter,
+            key_fn,
+            last_key: None,
+        }
+    }
+}
+
+impl<I, K, F> Iterator for DedupByKeyIterator<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let key = (self.key_fn)(&item);
+            // Only the first item of each run of equal keys is kept
+            if self.last_key.as_ref() != Some(&key) {
+                self.last_key = Some(key);
+                return Some(item);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Statistical helpers: `running_median`, `mean`, `variance`, `stddev`
+
+#![allow(clippy::missing_const_for_fn)]
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// `f64` ordered by `total_cmp` so it can live in a `BinaryHeap`
+#[derive(Debug, Clone, Copy)]
+struct TotalF64(f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Iterator that emits the median of all elements seen so far
+///
+/// Uses the two-heap method: a max-heap holds the lower half and a min-heap
+/// the upper half, so each step costs O(log n).
+pub struct RunningMedianIterator<I> {
+    iter: I,
+    lower: BinaryHeap<TotalF64>,
+    upper: BinaryHeap<Reverse<TotalF64>>,
+}
+
+impl<I> RunningMedianIterator<I>
+where
+    I: Iterator,
+    I::Item: Into<f64>,
+{
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter,
+            lower: BinaryHeap::new(),
+            upper: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<I> Iterator for RunningMedianIterator<I>
+where
+    I: Iterator,
+    I::Item: Into<f64>,
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = TotalF64(self.iter.next()?.into());
+
+        match self.lower.peek() {
+            Some(top) if value > *top => self.upper.push(Reverse(value)),
+            _ => self.lower.push(value),
+        }
+
+        // Rebalance so lower holds the extra element when the count is odd
+        if self.lower.len() > self.upper.len() + 1 {
+            if let Some(top) = self.lower.pop() {
+                self.upper.push(Reverse(top));
+            }
+        } else if self.upper.len() > self.lower.len() {
+            if let Some(Reverse(top)) = self.upper.pop() {
+                self.lower.push(top);
+            }
+        }
+
+        let lower_top = self.lower.peek().map(|v| v.0)?;
+        if self.lower.len() > self.upper.len() {
+            Some(lower_top)
+        } else {
+            let upper_top = self.upper.peek().map(|v| v.0 .0)?;
+            Some(f64::midpoint(lower_top, upper_top))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Single-pass mean and variance accumulator (Welford's algorithm)
+#[derive(Debug, Default)]
+pub struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    /// Accumulate every value of an iterator
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_values<I: Iterator<Item = f64>>(values: I) -> Self {
+        let mut acc = Self::default();
+        for x in values {
+            acc.count += 1;
+            let delta = x - acc.mean;
+            acc.mean += delta / acc.count as f64;
+            acc.m2 += delta * (x - acc.mean);
+        }
+        acc
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Population variance
+    #[allow(clippy::cast_precision_loss)]
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+}
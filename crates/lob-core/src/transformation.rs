@@ -0,0 +1,244 @@
+//! Transformation iterators: `tee`, `intersperse`, `zip_longest`, `with_position`
+
+#![allow(clippy::missing_const_for_fn)]
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::iter::{Fuse, Peekable};
+use std::rc::Rc;
+
+/// Source and pending items shared by the two halves of a `tee`
+struct TeeBuffer<I: Iterator> {
+    iter: I,
+    buffer: VecDeque<I::Item>,
+    /// Which half the buffered items are waiting for
+    owner: bool,
+}
+
+/// One half of a `tee`, replaying items the other half has already pulled
+pub struct TeeIterator<I: Iterator> {
+    shared: Rc<RefCell<TeeBuffer<I>>>,
+    id: bool,
+}
+
+impl<I: Iterator> TeeIterator<I>
+where
+    I::Item: Clone,
+{
+    pub fn new(iter: I) -> (Self, Self) {
+        let shared = Rc::new(RefCell::new(TeeBuffer {
+            iter,
+            buffer: VecDeque::new(),
+            owner: false,
+        }));
+        (
+            Self {
+                shared: Rc::clone(&shared),
+                id: true,
+            },
+            Self { shared, id: false },
+        )
+    }
+}
+
+impl<I: Iterator> Iterator for TeeIterator<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut shared = self.shared.borrow_mut();
+
+        // Replay items the other half pulled first
+        if shared.owner == self.id {
+            if let Some(item) = shared.buffer.pop_front() {
+                return Some(item);
+            }
+        }
+
+        // We are in the lead: pull from the source and queue a copy
+        let item = shared.iter.next()?;
+        shared.owner = !self.id;
+        shared.buffer.push_back(item.clone());
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let shared = self.shared.borrow();
+        let (lower, upper) = shared.iter.size_hint();
+        let buffered = if shared.owner == self.id {
+            shared.buffer.len()
+        } else {
+            0
+        };
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|u| u.checked_add(buffered)),
+        )
+    }
+}
+
+/// Iterator that yields a separator between consecutive elements
+pub struct IntersperseIterator<I: Iterator> {
+    iter: Peekable<I>,
+    sep: I::Item,
+    needs_sep: bool,
+}
+
+impl<I: Iterator> IntersperseIterator<I>
+where
+    I::Item: Clone,
+{
+    pub fn new(iter: I, sep: I::Item) -> Self {
+        Self {
+            iter: iter.peekable(),
+            sep,
+            needs_sep: false,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for IntersperseIterator<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Only emit a separator when another element follows it
+        if self.needs_sep && self.iter.peek().is_some() {
+            self.needs_sep = false;
+            return Some(self.sep.clone());
+        }
+
+        let item = self.iter.next()?;
+        self.needs_sep = true;
+        Some(item)
+    }
+}
+
+/// Iterator that zips two sides until both are exhausted
+pub struct ZipLongestIterator<I, J> {
+    left: Fuse<I>,
+    right: Fuse<J>,
+}
+
+impl<I: Iterator, J: Iterator> ZipLongestIterator<I, J> {
+    pub fn new(left: I, right: J) -> Self {
+        Self {
+            left: left.fuse(),
+            right: right.fuse(),
+        }
+    }
+}
+
+impl<I: Iterator, J: Iterator> Iterator for ZipLongestIterator<I, J> {
+    type Item = (Option<I::Item>, Option<J::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.left.next(), self.right.next()) {
+            (None, None) => None,
+            pair => Some(pair),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_lower, left_upper) = self.left.size_hint();
+        let (right_lower, right_upper) = self.right.size_hint();
+        let upper = match (left_upper, right_upper) {
+            (Some(l), Some(r)) => Some(l.max(r)),
+            _ => None,
+        };
+        (left_lower.max(right_lower), upper)
+    }
+}
+
+/// Where an element sits in its stream, as tagged by `with_position`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Position {
+    /// First of several elements
+    First,
+    /// Neither first nor last
+    Middle,
+    /// Last of several elements
+    Last,
+    /// The only element
+    Only,
+}
+
+/// Iterator that pairs each element with its `Position`
+pub struct WithPositionIterator<I: Iterator> {
+    iter: Peekable<I>,
+    started: bool,
+}
+
+impl<I: Iterator> WithPositionIterator<I> {
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: iter.peekable(),
+            started: false,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for WithPositionIterator<I> {
+    type Item = (Position, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        // One element of lookahead tells whether this one is the last
+        let is_last = self.iter.peek().is_none();
+        let position = match (self.started, is_last) {
+            (false, true) => Position::Only,
+            (false, false) => Position::First,
+            (true, false) => Position::Middle,
+            (true, true) => Position::Last,
+        };
+        self.started = true;
+        Some((position, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator that yields a default element if the source turns out empty
+pub struct DefaultIfEmptyIterator<I: Iterator> {
+    iter: I,
+    // Dropped as soon as the source yields anything
+    default: Option<I::Item>,
+}
+
+impl<I: Iterator> DefaultIfEmptyIterator<I> {
+    pub fn new(iter: I, default: I::Item) -> Self {
+        Self {
+            iter,
+            default: Some(default),
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for DefaultIfEmptyIterator<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(item) => {
+                self.default = None;
+                Some(item)
+            }
+            None => self.default.take(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if self.default.is_some() {
+            (lower.max(1), upper.map(|u| u.max(1)))
+        } else {
+            (lower, upper)
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Tests for columnar collection (requires the `columnar` feature)
+
+#![cfg(feature = "columnar")]
+
+use lob_core::{ColumnValue, IntoRow, LobExt};
+use std::collections::HashMap;
+
+struct Reading {
+    sensor: &'static str,
+    value: f64,
+    ok: bool,
+}
+
+impl IntoRow for Reading {
+    fn into_row(self) -> Vec<(String, ColumnValue)> {
+        vec![
+            ("sensor".to_string(), self.sensor.into()),
+            ("value".to_string(), self.value.into()),
+            ("ok".to_string(), self.ok.into()),
+        ]
+    }
+}
+
+#[test]
+fn collect_columns_struct_stream() {
+    let batch = vec![
+        Reading {
+            sensor: "a",
+            value: 1.5,
+            ok: true,
+        },
+        Reading {
+            sensor: "b",
+            value: 2.0,
+            ok: false,
+        },
+        Reading {
+            sensor: "a",
+            value: 0.5,
+            ok: true,
+        },
+    ]
+    .into_iter()
+    .lob()
+    .collect_columns();
+
+    assert_eq!(batch.num_rows(), 3);
+    assert_eq!(batch.num_columns(), 3);
+    assert_eq!(batch.column_names(), &["sensor", "value", "ok"]);
+    assert_eq!(
+        batch.column("value").unwrap(),
+        &[
+            ColumnValue::Float(1.5),
+            ColumnValue::Float(2.0),
+            ColumnValue::Float(0.5)
+        ]
+    );
+    assert_eq!(
+        batch.column("ok").unwrap(),
+        &[
+            ColumnValue::Bool(true),
+            ColumnValue::Bool(false),
+            ColumnValue::Bool(true)
+        ]
+    );
+    assert!(batch.column("missing").is_none());
+}
+
+#[test]
+fn collect_columns_pads_missing_fields() {
+    let rows: Vec<HashMap<String, String>> = vec![
+        HashMap::from([("a".to_string(), "1".to_string())]),
+        HashMap::from([("b".to_string(), "2".to_string())]),
+    ];
+
+    let batch = rows.into_iter().lob().collect_columns();
+
+    assert_eq!(batch.num_rows(), 2);
+    for (_, values) in batch.clone().into_columns() {
+        assert_eq!(values.len(), 2);
+    }
+    assert_eq!(
+        batch.column("a").unwrap(),
+        &[ColumnValue::Str("1".to_string()), ColumnValue::Null]
+    );
+    assert_eq!(
+        batch.column("b").unwrap(),
+        &[ColumnValue::Null, ColumnValue::Str("2".to_string())]
+    );
+}
+
+#[test]
+fn collect_columns_empty() {
+    let batch = Vec::<Reading>::new().into_iter().lob().collect_columns();
+    assert_eq!(batch.num_rows(), 0);
+    assert_eq!(batch.num_columns(), 0);
+}
@@ -1,6 +1,7 @@
 //! Comprehensive tests for grouping operations
 
 use lob_core::LobExt;
+use std::collections::HashMap;
 
 #[test]
 fn chunk_basic() {
@@ -32,6 +33,48 @@ fn chunk_empty() {
     assert!(result.is_empty());
 }
 
+#[test]
+fn chunk_bytes_fills_to_budget() {
+    // 4 + 3 + 3 = 10 bytes fit exactly; "ccccc" starts the next chunk
+    let lines = vec!["aaaa", "bbb", "ccc", "ddddd", "ee"];
+    let result: Vec<_> = lines.into_iter().lob().chunk_bytes(10).collect();
+    assert_eq!(
+        result,
+        vec![vec!["aaaa", "bbb", "ccc"], vec!["ddddd", "ee"]]
+    );
+}
+
+#[test]
+fn chunk_bytes_oversized_item_alone() {
+    let lines = vec!["ab".to_string(), "x".repeat(20), "cd".to_string()];
+    let result: Vec<_> = lines.into_iter().lob().chunk_bytes(8).collect();
+    assert_eq!(
+        result,
+        vec![
+            vec!["ab".to_string()],
+            vec!["x".repeat(20)],
+            vec!["cd".to_string()]
+        ]
+    );
+}
+
+#[test]
+fn chunk_bytes_counts_utf8_bytes() {
+    // "é" is 2 bytes, so only two fit in 5 bytes
+    let result: Vec<_> = vec!["é", "é", "é"]
+        .into_iter()
+        .lob()
+        .chunk_bytes(5)
+        .collect();
+    assert_eq!(result, vec![vec!["é", "é"], vec!["é"]]);
+}
+
+#[test]
+fn chunk_bytes_empty() {
+    let result: Vec<Vec<&str>> = Vec::new().into_iter().lob().chunk_bytes(10).collect();
+    assert!(result.is_empty());
+}
+
 #[test]
 fn window_basic() {
     let result: Vec<_> = (1..=5).lob().window(3).collect();
@@ -62,6 +105,63 @@ fn window_empty() {
     assert!(result.is_empty());
 }
 
+#[test]
+fn window_step_non_overlapping_drops_trailing() {
+    let result: Vec<_> = (1..=5).lob().window_step(2, 2).collect();
+    assert_eq!(result, vec![vec![1, 2], vec![3, 4]]);
+}
+
+#[test]
+fn window_step_one_matches_window() {
+    let stepped: Vec<_> = (1..=6).lob().window_step(3, 1).collect();
+    let plain: Vec<_> = (1..=6).lob().window(3).collect();
+    assert_eq!(stepped, plain);
+}
+
+#[test]
+fn window_step_overlapping_and_gapped() {
+    let overlapping: Vec<_> = (1..=7).lob().window_step(3, 2).collect();
+    assert_eq!(
+        overlapping,
+        vec![vec![1, 2, 3], vec![3, 4, 5], vec![5, 6, 7]]
+    );
+    let gapped: Vec<_> = (1..=8).lob().window_step(2, 3).collect();
+    assert_eq!(gapped, vec![vec![1, 2], vec![4, 5], vec![7, 8]]);
+}
+
+#[test]
+#[should_panic(expected = "window step must be greater than 0")]
+fn window_step_zero_panics() {
+    let _ = (1..=3).lob().window_step(2, 0).collect::<Vec<_>>();
+}
+
+#[test]
+fn pairs_adjacent() {
+    assert_eq!(
+        (1..=4).lob().pairs().to_list(),
+        vec![(1, 2), (2, 3), (3, 4)]
+    );
+}
+
+#[test]
+fn pairs_too_short() {
+    assert!((1..=1).lob().pairs().to_list().is_empty());
+    assert!((1..1).lob().pairs().to_list().is_empty());
+}
+
+#[test]
+fn pairs_matches_window_two() {
+    let words = vec!["a", "b", "c", "d", "e"];
+    let pairs: Vec<_> = words.clone().into_iter().lob().pairs().collect();
+    let windows: Vec<_> = words
+        .into_iter()
+        .lob()
+        .window(2)
+        .map(|w| (w[0], w[1]))
+        .collect();
+    assert_eq!(pairs, windows);
+}
+
 #[test]
 fn window_exact_size() {
     let result: Vec<_> = vec![1, 2, 3].into_iter().lob().window(3).collect();
@@ -144,7 +244,7 @@ fn chunk_then_map() {
 #[test]
 fn group_by_iterator_exhaustion() {
     let data = vec![1, 2, 3, 4];
-    let mut groups = data.into_iter().lob().group_by(|x| x % 2).into_iter();
+    let mut groups = data.into_iter().lob().group_by(|x| x % 2);
 
     // Get all groups
     let all: Vec<_> = groups.by_ref().collect();
@@ -157,7 +257,7 @@ fn group_by_iterator_exhaustion() {
 #[test]
 fn group_by_size_hint() {
     let data = vec![1, 2, 3];
-    let mut groups = data.into_iter().lob().group_by(|x| x % 2).into_iter();
+    let mut groups = data.into_iter().lob().group_by(|x| x % 2);
 
     // Before consuming
     let (lower, _upper) = groups.size_hint();
@@ -173,8 +273,403 @@ fn group_by_size_hint() {
 #[test]
 fn window_iterator_size_hint() {
     let data = vec![1, 2, 3, 4, 5];
-    let windows = data.into_iter().lob().window(3).into_iter();
+    let windows = data.into_iter().lob().window(3);
 
     let (lower, _upper) = windows.size_hint();
     assert_eq!(lower, 0);
 }
+
+#[test]
+fn nested_counts_two_levels() {
+    let data = vec![
+        ("us", "nyc"),
+        ("us", "la"),
+        ("us", "nyc"),
+        ("fr", "paris"),
+        ("fr", "lyon"),
+        ("fr", "paris"),
+        ("fr", "paris"),
+    ];
+    let counts = data.into_iter().lob().nested_counts(|x| x.0, |x| x.1);
+
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts["us"].len(), 2);
+    assert_eq!(counts["us"]["nyc"], 2);
+    assert_eq!(counts["us"]["la"], 1);
+    assert_eq!(counts["fr"]["paris"], 3);
+    assert_eq!(counts["fr"]["lyon"], 1);
+}
+
+#[test]
+fn nested_counts_empty() {
+    let data: Vec<(i32, i32)> = vec![];
+    let counts = data.into_iter().lob().nested_counts(|x| x.0, |x| x.1);
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn ngrams_bigrams() {
+    let words = "to be or not to be".split(' ');
+    let result: Vec<_> = words.lob().ngrams(2).collect();
+    assert_eq!(
+        result,
+        vec![
+            vec!["to", "be"],
+            vec!["be", "or"],
+            vec!["or", "not"],
+            vec!["not", "to"],
+            vec!["to", "be"],
+        ]
+    );
+}
+
+#[test]
+fn ngram_counts_bigrams() {
+    let words = "to be or not to be".split(' ');
+    let counts = words.lob().ngram_counts(2);
+
+    assert_eq!(counts.len(), 4);
+    assert_eq!(counts[&vec!["to", "be"]], 2);
+    assert_eq!(counts[&vec!["be", "or"]], 1);
+    assert_eq!(counts[&vec!["not", "to"]], 1);
+}
+
+#[test]
+fn ngram_counts_too_short() {
+    let counts = vec!["only"].into_iter().lob().ngram_counts(2);
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn group_by_preserves_first_seen_order() {
+    let groups: Vec<_> = vec![3, 1, 3, 2, 1]
+        .into_iter()
+        .lob()
+        .group_by(|x| *x)
+        .collect();
+
+    let keys: Vec<_> = groups.iter().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![3, 1, 2]);
+    assert_eq!(groups[0].1, vec![3, 3]);
+    assert_eq!(groups[1].1, vec![1, 1]);
+    assert_eq!(groups[2].1, vec![2]);
+}
+
+#[test]
+fn group_consecutive_separate_runs() {
+    let result: Vec<_> = vec![1, 1, 2, 2, 2, 1]
+        .into_iter()
+        .lob()
+        .group_consecutive(|x| *x)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![(1, vec![1, 1]), (2, vec![2, 2, 2]), (1, vec![1])]
+    );
+}
+
+#[test]
+fn group_consecutive_by_derived_key() {
+    let result: Vec<_> = vec!["apple", "avocado", "banana", "cherry", "cranberry"]
+        .into_iter()
+        .lob()
+        .group_consecutive(|s| s.chars().next().unwrap())
+        .map(|(k, v)| (k, v.len()))
+        .collect();
+
+    assert_eq!(result, vec![('a', 2), ('b', 1), ('c', 2)]);
+}
+
+#[test]
+fn group_consecutive_empty() {
+    let result: Vec<(i32, Vec<i32>)> = vec![]
+        .into_iter()
+        .lob()
+        .group_consecutive(|x: &i32| *x)
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn group_consecutive_is_lazy() {
+    // Works on an infinite source as long as only finitely many groups are taken
+    let result: Vec<_> = (0..).lob().group_consecutive(|x| x / 3).take(2).collect();
+    assert_eq!(result, vec![(0, vec![0, 1, 2]), (1, vec![3, 4, 5])]);
+}
+
+#[test]
+fn chunk_step_equal_matches_chunk() {
+    let stepped: Vec<_> = (0..7).lob().chunk_step(3, 3).collect();
+    let chunked: Vec<_> = (0..7).lob().chunk(3).collect();
+    assert_eq!(stepped, chunked);
+}
+
+#[test]
+fn chunk_step_less_than_size_overlaps() {
+    let result: Vec<_> = (0..5).lob().chunk_step(3, 2).collect();
+    // [4] alone would only repeat the tail of [2, 3, 4], so it is not emitted
+    assert_eq!(result, vec![vec![0, 1, 2], vec![2, 3, 4]]);
+}
+
+#[test]
+fn chunk_step_greater_than_size_skips() {
+    let result: Vec<_> = (0..10).lob().chunk_step(2, 3).collect();
+    assert_eq!(result, vec![vec![0, 1], vec![3, 4], vec![6, 7], vec![9]]);
+}
+
+#[test]
+fn chunk_step_gap_past_end() {
+    let result: Vec<_> = (0..3).lob().chunk_step(2, 5).collect();
+    assert_eq!(result, vec![vec![0, 1]]);
+}
+
+#[test]
+#[should_panic(expected = "chunk step must be greater than 0")]
+fn chunk_step_zero_step_panics() {
+    let _ = (0..3).lob().chunk_step(2, 0);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be greater than 0")]
+fn chunk_step_zero_size_panics() {
+    let _ = (0..3).lob().chunk_step(0, 1);
+}
+
+#[test]
+fn window_array_pairs() {
+    let result: Vec<_> = (1..=4).lob().window_array::<2>().collect();
+    assert_eq!(result, vec![[1, 2], [2, 3], [3, 4]]);
+}
+
+#[test]
+fn window_array_matches_window() {
+    let arrays: Vec<Vec<i32>> = (0..10)
+        .lob()
+        .window_array::<3>()
+        .map(|w| w.to_vec())
+        .collect();
+    let vecs: Vec<_> = (0..10).lob().window(3).collect();
+    assert_eq!(arrays, vecs);
+}
+
+#[test]
+fn window_array_shorter_than_n() {
+    let result: Vec<[i32; 3]> = vec![1, 2].into_iter().lob().window_array::<3>().collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn moving_average_pairs() {
+    let result: Vec<_> = vec![1, 2, 3, 4]
+        .into_iter()
+        .lob()
+        .moving_average(2)
+        .collect();
+    assert_eq!(result, vec![1.5, 2.5, 3.5]);
+}
+
+#[test]
+fn window_reduce_matches_window_map() {
+    let reduced: Vec<i32> = (0..8)
+        .lob()
+        .window_reduce(3, |w| w.iter().max().copied().unwrap())
+        .collect();
+    let mapped: Vec<i32> = (0..8)
+        .lob()
+        .window(3)
+        .map(|w| w.into_iter().max().unwrap())
+        .collect();
+    assert_eq!(reduced, mapped);
+}
+
+#[test]
+fn window_reduce_too_short() {
+    let result: Vec<usize> = vec![1, 2]
+        .into_iter()
+        .lob()
+        .window_reduce(3, std::collections::VecDeque::len)
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn rolling_matches_naive_window_map() {
+    let data = vec![5, 3, 8, 1, 9, 2, 7, 4, 6, 0, 11];
+    for n in 1..=5 {
+        let rolled: Vec<Vec<i32>> = data
+            .clone()
+            .into_iter()
+            .lob()
+            .rolling(n, <[i32]>::to_vec)
+            .collect();
+        let naive: Vec<Vec<i32>> = data.clone().into_iter().lob().window(n).collect();
+        assert_eq!(rolled, naive, "window size {n}");
+    }
+}
+
+#[test]
+fn rolling_range() {
+    let result: Vec<i32> = vec![3, 1, 4, 1, 5, 9]
+        .into_iter()
+        .lob()
+        .rolling(3, |w| w.iter().max().unwrap() - w.iter().min().unwrap())
+        .collect();
+    assert_eq!(result, vec![3, 3, 4, 8]);
+}
+
+#[test]
+fn rolling_too_short() {
+    let result: Vec<usize> = vec![1, 2]
+        .into_iter()
+        .lob()
+        .rolling(3, <[i32]>::len)
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn batching_by_running_total() {
+    let result: Vec<Vec<i32>> = (1..=10)
+        .peekable()
+        .lob()
+        .batching(|it| {
+            let mut batch = vec![it.next()?];
+            let mut total = batch[0];
+            while let Some(x) = it.next_if(|x| total + x <= 6) {
+                total += x;
+                batch.push(x);
+            }
+            Some(batch)
+        })
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            vec![1, 2, 3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![10]
+        ]
+    );
+    for batch in result.iter().filter(|b| b.len() > 1) {
+        assert!(batch.iter().sum::<i32>() <= 6);
+    }
+}
+
+#[test]
+fn batching_empty() {
+    let result: Vec<i32> = Vec::<i32>::new()
+        .into_iter()
+        .lob()
+        .batching(Iterator::next)
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn group_reduce_sums_per_key() {
+    let totals = vec![("a", 1), ("b", 2), ("a", 3)]
+        .into_iter()
+        .lob()
+        .group_reduce(|x| x.0, || 0, |acc, x| *acc += x.1);
+    assert_eq!(totals, HashMap::from([("a", 4), ("b", 2)]));
+}
+
+#[test]
+fn group_reduce_non_copy_accumulator() {
+    let words = vec!["apple", "avocado", "banana", "blueberry", "cherry"];
+    let by_letter = words.into_iter().lob().group_reduce(
+        |w| w.chars().next().unwrap(),
+        String::new,
+        |acc, w| {
+            if !acc.is_empty() {
+                acc.push(',');
+            }
+            acc.push_str(w);
+        },
+    );
+    assert_eq!(by_letter[&'a'], "apple,avocado");
+    assert_eq!(by_letter[&'b'], "banana,blueberry");
+    assert_eq!(by_letter.len(), 3);
+}
+
+#[test]
+fn group_reduce_init_called_once_per_key() {
+    let mut inits = 0;
+    let counts = (0..10).lob().group_reduce(
+        |x| x % 3,
+        || {
+            inits += 1;
+            0usize
+        },
+        |acc, _| *acc += 1,
+    );
+    assert_eq!(inits, 3);
+    assert_eq!(counts[&0], 4);
+}
+
+#[test]
+fn group_reduce_empty() {
+    let totals =
+        Vec::<(u8, i32)>::new()
+            .into_iter()
+            .lob()
+            .group_reduce(|x| x.0, || 0, |acc, x| *acc += x.1);
+    assert!(totals.is_empty());
+}
+
+#[test]
+fn sum_by_key_per_key_totals() {
+    let totals = vec![("a", 10), ("b", 5), ("a", 3)]
+        .into_iter()
+        .lob()
+        .sum_by_key(|r| r.0, |r| r.1);
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals["a"], 13);
+    assert_eq!(totals["b"], 5);
+}
+
+#[test]
+fn sum_by_key_floats() {
+    let totals = vec![("x", 0.5), ("x", 1.25)]
+        .into_iter()
+        .lob()
+        .sum_by_key(|r| r.0, |r| r.1);
+    assert!((totals["x"] - 1.75_f64).abs() < f64::EPSILON);
+}
+
+#[test]
+fn group_into_map_by_parity() {
+    let groups = (1..=6).lob().group_into_map(|x| x % 2);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[&0], vec![2, 4, 6]);
+    assert_eq!(groups[&1], vec![1, 3, 5]);
+}
+
+#[test]
+fn group_into_map_empty() {
+    let groups = Vec::<i32>::new().into_iter().lob().group_into_map(|x| *x);
+    assert!(groups.is_empty());
+}
+
+#[test]
+fn dedup_counts_runs() {
+    let result: Vec<_> = vec![1, 1, 1, 2, 3, 3]
+        .into_iter()
+        .lob()
+        .dedup_counts()
+        .collect();
+    assert_eq!(result, vec![(3, 1), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn dedup_counts_empty() {
+    let empty: Vec<i32> = vec![];
+    assert_eq!(empty.into_iter().lob().dedup_counts().count(), 0);
+}
@@ -170,11 +170,7 @@ fn inner_join_with_iteration() {
     let left = vec![(1, "a"), (2, "b"), (3, "c")];
     let right = vec![(1, "x"), (2, "y"), (2, "z")];
 
-    let mut iter = left
-        .into_iter()
-        .lob()
-        .join_inner(right, |x| x.0, |x| x.0)
-        .into_iter();
+    let mut iter = left.into_iter().lob().join_inner(right, |x| x.0, |x| x.0);
 
     let first = iter.next();
     assert!(first.is_some());
@@ -195,11 +191,7 @@ fn left_join_with_iteration() {
     let left = vec![(1, "a"), (2, "b"), (3, "c")];
     let right = vec![(1, "x"), (2, "y")];
 
-    let iter = left
-        .into_iter()
-        .lob()
-        .join_left(right, |x| x.0, |x| x.0)
-        .into_iter();
+    let iter = left.into_iter().lob().join_left(right, |x| x.0, |x| x.0);
 
     // Should get all left items
     let mut count = 0;
@@ -300,3 +292,292 @@ fn join_with_filter() {
 
     assert_eq!(result.len(), 3);
 }
+
+#[test]
+fn anti_join_some_match() {
+    let left = vec![(1, "a"), (2, "b"), (3, "c")];
+    let right = vec![(1, "x"), (3, "y")];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_anti(right, |x| x.0, |x| x.0)
+        .collect();
+
+    assert_eq!(result, vec![(2, "b")]);
+}
+
+#[test]
+fn anti_join_all_match() {
+    let left = vec![(1, "a"), (2, "b")];
+    let right = vec![(1, "x"), (2, "y"), (2, "z")];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_anti(right, |x| x.0, |x| x.0)
+        .collect();
+
+    assert!(result.is_empty());
+}
+
+#[test]
+fn anti_join_none_match() {
+    let left = vec![(1, "a"), (2, "b")];
+    let right = vec![(3, "x")];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_anti(right, |x| x.0, |x| x.0)
+        .collect();
+
+    assert_eq!(result, vec![(1, "a"), (2, "b")]);
+}
+
+#[test]
+fn anti_join_left_not_clone() {
+    struct Row(i32);
+
+    let left = vec![Row(1), Row(2)];
+    let right = vec![2];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_anti(right, |r| r.0, |k| *k)
+        .map(|r| r.0)
+        .collect();
+
+    assert_eq!(result, vec![1]);
+}
+
+#[test]
+fn inner_join_fan_out_clones_left_once_per_extra_match() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct Counted {
+        id: i32,
+        clones: Rc<Cell<usize>>,
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            Self {
+                id: self.id,
+                clones: Rc::clone(&self.clones),
+            }
+        }
+    }
+
+    let clones = Rc::new(Cell::new(0));
+    let left = vec![Counted {
+        id: 1,
+        clones: Rc::clone(&clones),
+    }];
+    let right: Vec<(i32, usize)> = (0..1000).map(|i| (1, i)).collect();
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_inner(right, |l| l.id, |r| r.0)
+        .collect();
+
+    assert_eq!(result.len(), 1000);
+    assert!(result.iter().all(|(l, _)| l.id == 1));
+    // 1000 matches need 1000 left values: 999 clones plus the moved original
+    assert_eq!(clones.get(), 999);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sale {
+    region: String,
+    date: &'static str,
+    amount: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Weather {
+    region: String,
+    date: &'static str,
+    sunny: bool,
+}
+
+fn sale(region: &str, date: &'static str, amount: u32) -> Sale {
+    Sale {
+        region: region.to_string(),
+        date,
+        amount,
+    }
+}
+
+fn weather(region: &str, date: &'static str, sunny: bool) -> Weather {
+    Weather {
+        region: region.to_string(),
+        date,
+        sunny,
+    }
+}
+
+#[test]
+fn inner_join_composite_key() {
+    let sales = vec![
+        sale("eu", "2024-01-01", 10),
+        sale("eu", "2024-01-02", 20),
+        sale("us", "2024-01-01", 30),
+    ];
+    let weather = vec![
+        weather("eu", "2024-01-01", true),
+        weather("us", "2024-01-02", false),
+        weather("us", "2024-01-01", false),
+    ];
+
+    let result: Vec<_> = sales
+        .into_iter()
+        .lob()
+        .join_inner(
+            weather,
+            |s| (s.region.clone(), s.date),
+            |w| (w.region.clone(), w.date),
+        )
+        .map(|(s, w)| (s.region, s.date, s.amount, w.sunny))
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            ("eu".to_string(), "2024-01-01", 10, true),
+            ("us".to_string(), "2024-01-01", 30, false),
+        ]
+    );
+}
+
+#[test]
+fn left_join_composite_key() {
+    let sales = vec![sale("eu", "2024-01-01", 10), sale("eu", "2024-01-02", 20)];
+    let weather = vec![weather("eu", "2024-01-01", true)];
+
+    let result: Vec<_> = sales
+        .into_iter()
+        .lob()
+        .join_left(
+            weather,
+            |s| (s.region.clone(), s.date),
+            |w| (w.region.clone(), w.date),
+        )
+        .map(|(s, w)| (s.date, w.map(|w| w.sunny)))
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![("2024-01-01", Some(true)), ("2024-01-02", None)]
+    );
+}
+
+#[test]
+fn inner_join_composite_key_partial_match_excluded() {
+    // Same region, different date must not match
+    let left = vec![("eu", 1), ("eu", 2)];
+    let right = vec![("eu", 2, "hit"), ("us", 1, "miss")];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_inner(right, |l| (l.0, l.1), |r| (r.0, r.1))
+        .collect();
+
+    assert_eq!(result, vec![(("eu", 2), ("eu", 2, "hit"))]);
+}
+
+#[test]
+fn cross_join_all_pairs_left_major() {
+    let result: Vec<_> = vec![1, 2]
+        .into_iter()
+        .lob()
+        .cross_join(["a", "b"])
+        .collect();
+    assert_eq!(result, vec![(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+}
+
+#[test]
+fn cross_join_empty_side() {
+    let empty_right: Vec<_> = vec![1, 2]
+        .into_iter()
+        .lob()
+        .cross_join(Vec::<i32>::new())
+        .collect();
+    assert!(empty_right.is_empty());
+
+    let empty_left: Vec<(i32, i32)> = Vec::new().into_iter().lob().cross_join([1, 2]).collect();
+    assert!(empty_left.is_empty());
+}
+
+#[test]
+fn cross_join_self_pairs() {
+    let words = vec!["x".to_string(), "y".to_string()];
+    let result: Vec<_> = words
+        .clone()
+        .into_iter()
+        .lob()
+        .cross_join(words)
+        .filter(|(a, b)| a != b)
+        .collect();
+    assert_eq!(
+        result,
+        vec![
+            ("x".to_string(), "y".to_string()),
+            ("y".to_string(), "x".to_string())
+        ]
+    );
+}
+
+#[test]
+fn inner_join_stream_matches_inner_join() {
+    let left = vec![(1, "a"), (2, "b"), (2, "bb"), (3, "c")];
+    let right = vec![(2, "x"), (1, "y"), (2, "z"), (5, "w")];
+
+    let mut buffered_right: Vec<_> = left
+        .clone()
+        .into_iter()
+        .lob()
+        .join_inner(right.clone(), |l| l.0, |r| r.0)
+        .collect();
+    let mut buffered_left: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_inner_stream(right, |l| l.0, |r| r.0)
+        .collect();
+
+    buffered_right.sort_unstable();
+    buffered_left.sort_unstable();
+    assert_eq!(buffered_left, buffered_right);
+    assert_eq!(buffered_left.len(), 5);
+}
+
+#[test]
+fn inner_join_stream_follows_right_order() {
+    let left = vec![(1, "a"), (2, "b")];
+    let right = vec![(2, "x"), (1, "y")];
+
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_inner_stream(right, |l| l.0, |r| r.0)
+        .collect();
+
+    assert_eq!(result, vec![((2, "b"), (2, "x")), ((1, "a"), (1, "y"))]);
+}
+
+#[test]
+fn inner_join_stream_consumes_right_lazily() {
+    let left = vec![1, 2];
+    let result: Vec<_> = left
+        .into_iter()
+        .lob()
+        .join_inner_stream(1.., |l| *l, |r| *r)
+        .take(2)
+        .collect();
+    assert_eq!(result, vec![(1, 1), (2, 2)]);
+}
@@ -149,3 +149,196 @@ fn chained_selection() {
         .collect();
     assert_eq!(result, vec![4, 6, 8]);
 }
+
+/// Deterministic FNV-1a hasher for exercising `unique_with_hasher`
+#[derive(Default)]
+struct Fnv(u64);
+
+impl std::hash::Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn unique_with_hasher_dedups() {
+    let hasher = std::hash::BuildHasherDefault::<Fnv>::default();
+    let result: Vec<_> = vec!["b", "a", "b", "c", "a"]
+        .into_iter()
+        .lob()
+        .unique_with_hasher(hasher)
+        .collect();
+    assert_eq!(result, vec!["b", "a", "c"]);
+}
+
+#[test]
+fn unique_with_hasher_matches_unique() {
+    let data: Vec<u32> = (0..1000).map(|i| (i * 7919) % 101).collect();
+    let hasher = std::hash::BuildHasherDefault::<Fnv>::default();
+
+    let expected: Vec<_> = data.clone().into_iter().lob().unique().collect();
+    let result: Vec<_> = data.into_iter().lob().unique_with_hasher(hasher).collect();
+
+    assert_eq!(result, expected);
+    assert_eq!(result.len(), 101);
+}
+
+#[test]
+fn take_matching_includes_non_matching() {
+    let result: Vec<_> = vec!["a", "ERR1", "b", "c", "ERR2", "d", "ERR3"]
+        .into_iter()
+        .lob()
+        .take_matching(2, |x| x.starts_with("ERR"))
+        .collect();
+    assert_eq!(result, vec!["a", "ERR1", "b", "c", "ERR2"]);
+}
+
+#[test]
+fn take_matching_stops_without_pulling_more() {
+    let mut pulled = 0;
+    let result: Vec<_> = (0..10)
+        .inspect(|_| pulled += 1)
+        .lob()
+        .take_matching(1, |x| *x == 3)
+        .collect();
+    assert_eq!(result, vec![0, 1, 2, 3]);
+    assert_eq!(pulled, 4);
+}
+
+#[test]
+fn take_matching_fewer_matches_than_n() {
+    let result: Vec<_> = (0..5).lob().take_matching(10, |x| x % 2 == 0).collect();
+    assert_eq!(result, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn take_matching_zero() {
+    let result: Vec<_> = (0..5).lob().take_matching(0, |_| true).collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn take_until_includes_terminator() {
+    let result: Vec<_> = vec![1, 2, 3, 4]
+        .into_iter()
+        .lob()
+        .take_until(|x| *x == 3)
+        .collect();
+    assert_eq!(result, vec![1, 2, 3]);
+}
+
+#[test]
+fn take_until_no_match_takes_all() {
+    let result: Vec<_> = (0..4).lob().take_until(|x| *x > 10).collect();
+    assert_eq!(result, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn take_until_first_item_matches() {
+    let result: Vec<_> = (0..4).lob().take_until(|_| true).collect();
+    assert_eq!(result, vec![0]);
+}
+
+#[test]
+fn every_nth_basic() {
+    let result: Vec<_> = (0..10).lob().every_nth(3).collect();
+    assert_eq!(result, vec![0, 3, 6, 9]);
+}
+
+#[test]
+fn every_nth_matches_step_by() {
+    for n in 1..6 {
+        let ours: Vec<_> = (0..17).lob().every_nth(n).collect();
+        let std: Vec<_> = (0..17).step_by(n).collect();
+        assert_eq!(ours, std);
+    }
+}
+
+#[test]
+#[should_panic(expected = "every_nth step must be greater than 0")]
+fn every_nth_zero_panics() {
+    let _ = (0..10).lob().every_nth(0);
+}
+
+#[test]
+fn take_last_basic() {
+    let result: Vec<_> = (0..10).lob().take_last(3).collect();
+    assert_eq!(result, vec![7, 8, 9]);
+}
+
+#[test]
+fn take_last_more_than_available() {
+    let result: Vec<_> = (0..3).lob().take_last(10).collect();
+    assert_eq!(result, vec![0, 1, 2]);
+}
+
+#[test]
+fn take_last_zero() {
+    let result: Vec<_> = (0..3).lob().take_last(0).collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn skip_last_basic() {
+    let result: Vec<_> = (0..5).lob().skip_last(2).collect();
+    assert_eq!(result, vec![0, 1, 2]);
+}
+
+#[test]
+fn skip_last_more_than_available() {
+    let result: Vec<_> = (0..3).lob().skip_last(5).collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn skip_last_zero_is_noop() {
+    let result: Vec<_> = (0..3).lob().skip_last(0).collect();
+    assert_eq!(result, vec![0, 1, 2]);
+}
+
+#[test]
+fn skip_last_size_hint() {
+    let lob = (0..5).lob().skip_last(2);
+    assert_eq!(lob.size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn dedup_by_key_keeps_first_of_run() {
+    let result: Vec<_> = vec![(1, "a"), (1, "b"), (2, "c")]
+        .into_iter()
+        .lob()
+        .dedup_by_key(|x| x.0)
+        .collect();
+    assert_eq!(result, vec![(1, "a"), (2, "c")]);
+}
+
+#[test]
+fn dedup_by_key_only_adjacent() {
+    let result: Vec<_> = vec![1, 1, 2, 2, 1, 3, 3]
+        .into_iter()
+        .lob()
+        .dedup_by_key(|x| *x)
+        .collect();
+    assert_eq!(result, vec![1, 2, 1, 3]);
+}
+
+#[test]
+fn dedup_by_key_log_lines_differing_in_timestamp() {
+    let lines = vec![
+        "10:00:01 disk full",
+        "10:00:02 disk full",
+        "10:00:03 retrying",
+    ];
+    let result: Vec<_> = lines
+        .into_iter()
+        .lob()
+        .dedup_by_key(|l| l.split_once(' ').map(|(_, msg)| msg))
+        .collect();
+    assert_eq!(result, vec!["10:00:01 disk full", "10:00:03 retrying"]);
+}
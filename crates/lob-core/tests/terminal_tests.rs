@@ -82,6 +82,30 @@ fn max_single() {
     assert_eq!(max, Some(42));
 }
 
+#[test]
+fn max_by_partial_ord_floats() {
+    let max = vec![1.0, 3.5, 2.2]
+        .into_iter()
+        .lob()
+        .max_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(max, Some(3.5));
+}
+
+#[test]
+fn min_by_ties_return_first() {
+    let min = vec![(1, "a"), (0, "b"), (0, "c")]
+        .into_iter()
+        .lob()
+        .min_by(|x, y| x.0.cmp(&y.0));
+    assert_eq!(min, Some((0, "b")));
+}
+
+#[test]
+fn max_by_empty() {
+    let max = Vec::<f64>::new().into_iter().lob().max_by(f64::total_cmp);
+    assert_eq!(max, None);
+}
+
 #[test]
 fn first_basic() {
     let first = (1..10).lob().first();
@@ -148,6 +172,51 @@ fn fold_with_initial() {
     assert_eq!(sum, 25);
 }
 
+#[test]
+fn try_fold_success() {
+    let sum: Result<i32, String> = (1..=5).lob().try_fold(0, |a, b| Ok(a + b));
+    assert_eq!(sum, Ok(15));
+}
+
+#[test]
+fn try_fold_stops_at_error() {
+    let mut visited = Vec::new();
+    let result = vec![100u8, 100, 100, 1]
+        .into_iter()
+        .lob()
+        .try_fold(0u8, |acc, x| {
+            visited.push(x);
+            acc.checked_add(x)
+                .ok_or_else(|| format!("overflow at {acc} + {x}"))
+        });
+    assert_eq!(result, Err("overflow at 200 + 100".to_string()));
+    assert_eq!(visited, vec![100, 100, 100]);
+}
+
+#[test]
+fn fold_while_breaks_on_infinite_stream() {
+    use std::ops::ControlFlow;
+    let total = (1..).lob().fold_while(0, |acc, x| {
+        let acc = acc + x;
+        if acc > 10 {
+            ControlFlow::Break(acc)
+        } else {
+            ControlFlow::Continue(acc)
+        }
+    });
+    // 1 + 2 + 3 + 4 = 10, then + 5 exceeds the limit
+    assert_eq!(total, 15);
+}
+
+#[test]
+fn fold_while_without_break_folds_everything() {
+    use std::ops::ControlFlow;
+    let total = (1..=4)
+        .lob()
+        .fold_while(0, |acc, x| ControlFlow::Continue(acc + x));
+    assert_eq!(total, 10);
+}
+
 #[test]
 fn fold_empty() {
     let empty: Vec<i32> = vec![];
@@ -167,6 +236,47 @@ fn to_list_empty() {
     assert!(list.is_empty());
 }
 
+#[test]
+fn to_map_pairs() {
+    let map = vec![(1, "a"), (2, "b")].into_iter().lob().to_map();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&1], "a");
+    assert_eq!(map[&2], "b");
+}
+
+#[test]
+fn to_map_last_value_wins() {
+    let map = vec![("k", 1), ("k", 2)].into_iter().lob().to_map();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["k"], 2);
+}
+
+#[test]
+fn join_str_basic() {
+    let s = vec!["a", "b", "c"].into_iter().lob().join_str("-");
+    assert_eq!(s, "a-b-c");
+}
+
+#[test]
+fn join_str_single_item() {
+    let s = vec!["only".to_string()].into_iter().lob().join_str(", ");
+    assert_eq!(s, "only");
+}
+
+#[test]
+fn join_str_empty() {
+    let s = Vec::<&str>::new().into_iter().lob().join_str(",");
+    assert_eq!(s, "");
+}
+
+#[test]
+fn to_set_dedups() {
+    let set = vec![1, 1, 2].into_iter().lob().to_set();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&1));
+    assert!(set.contains(&2));
+}
+
 #[test]
 fn any_true() {
     let result = (1..10).lob().any(|x| x > 5);
@@ -204,3 +314,256 @@ fn all_empty() {
     let result: bool = empty.into_iter().lob().all(|x| x > 0);
     assert!(result); // Vacuous truth
 }
+
+#[test]
+fn find_map_first_parseable() {
+    let result = vec!["x", "12", "y"]
+        .into_iter()
+        .lob()
+        .find_map(|s| s.parse::<i32>().ok());
+    assert_eq!(result, Some(12));
+}
+
+#[test]
+fn find_map_none() {
+    let result = vec!["x", "y"]
+        .into_iter()
+        .lob()
+        .find_map(|s| s.parse::<i32>().ok());
+    assert_eq!(result, None);
+}
+
+#[test]
+fn index_of_found_and_missing() {
+    let words = vec!["a", "b", "c", "b"];
+    assert_eq!(words.clone().into_iter().lob().index_of(&"b"), Some(1));
+    assert_eq!(words.into_iter().lob().index_of(&"z"), None);
+}
+
+#[test]
+fn diff_lines_partial_overlap() {
+    let a = vec!["alice", "bob", "carol", "dave"];
+    let b = vec!["carol", "erin", "alice", "frank"];
+
+    let (only_a, only_b, both) = a.into_iter().lob().diff_lines(b);
+
+    assert_eq!(only_a, vec!["bob", "dave"]);
+    assert_eq!(only_b, vec!["erin", "frank"]);
+    assert_eq!(both, vec!["alice", "carol"]);
+}
+
+#[test]
+fn diff_lines_collapses_duplicates() {
+    let (only_a, only_b, both) = vec![1, 1, 2, 3, 3]
+        .into_iter()
+        .lob()
+        .diff_lines(vec![3, 4, 4]);
+
+    assert_eq!(only_a, vec![1, 2]);
+    assert_eq!(only_b, vec![4]);
+    assert_eq!(both, vec![3]);
+}
+
+#[test]
+fn diff_lines_disjoint_and_empty() {
+    let (only_a, only_b, both) = vec![1, 2].into_iter().lob().diff_lines(Vec::new());
+    assert_eq!(only_a, vec![1, 2]);
+    assert!(only_b.is_empty());
+    assert!(both.is_empty());
+}
+
+#[test]
+fn unzip_pairs() {
+    let (nums, chars): (Vec<_>, Vec<_>) = vec![(1, 'a'), (2, 'b')].into_iter().lob().unzip();
+    assert_eq!(nums, vec![1, 2]);
+    assert_eq!(chars, vec!['a', 'b']);
+}
+
+#[test]
+fn unzip_inverts_zip() {
+    let (left, right): (Vec<_>, Vec<_>) = (0..3).lob().zip(vec!["x", "y", "z"]).unzip();
+    assert_eq!(left, vec![0, 1, 2]);
+    assert_eq!(right, vec!["x", "y", "z"]);
+}
+
+#[test]
+fn unzip_empty() {
+    let (a, b): (Vec<i32>, Vec<char>) = Vec::<(i32, char)>::new().into_iter().lob().unzip();
+    assert!(a.is_empty());
+    assert!(b.is_empty());
+}
+
+#[test]
+fn exact_size_len() {
+    assert_eq!((0..10).lob().len(), 10);
+}
+
+#[test]
+fn size_hint_forwarded() {
+    assert_eq!((0..10).lob().size_hint(), (10, Some(10)));
+    assert_eq!((0..10).lob().take(3).size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn lob_is_an_iterator() {
+    let mut total = 0;
+    for x in (1..=4).lob().map(|x| x * 10) {
+        total += x;
+    }
+    assert_eq!(total, 100);
+
+    let mut lob = (0..3).lob();
+    assert_eq!(lob.next(), Some(0));
+    assert_eq!(lob.to_list(), vec![1, 2]);
+}
+
+#[test]
+fn mean_and_stddev() {
+    let data = vec![2, 4, 4, 4, 5, 5, 7, 9];
+    assert_eq!(data.clone().into_iter().lob().mean(), Some(5.0));
+    assert_eq!(data.clone().into_iter().lob().variance(), Some(4.0));
+    assert_eq!(data.into_iter().lob().stddev(), Some(2.0));
+}
+
+#[test]
+fn stats_empty_is_none() {
+    assert_eq!(Vec::<f64>::new().into_iter().lob().mean(), None);
+    assert_eq!(Vec::<f64>::new().into_iter().lob().variance(), None);
+    assert_eq!(Vec::<f64>::new().into_iter().lob().stddev(), None);
+}
+
+#[test]
+fn variance_large_offset_is_stable() {
+    let data = vec![1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0];
+    let var = data.into_iter().lob().variance().unwrap();
+    assert!((var - 22.5).abs() < 1e-6);
+}
+
+#[test]
+fn median_even_count() {
+    assert_eq!(vec![4, 1, 3, 2].into_iter().lob().median(), Some(2));
+}
+
+#[test]
+fn percentile_ninetieth() {
+    let latencies: Vec<u32> = (1..=50).rev().map(|x| x * 10).collect();
+    assert_eq!(latencies.into_iter().lob().percentile(0.9), Some(450));
+}
+
+#[test]
+fn percentile_inexact_products() {
+    // p * n is not exactly representable for these p
+    assert_eq!((1..=100).lob().percentile(0.07), Some(7));
+    assert_eq!((1..=100).lob().percentile(0.14), Some(14));
+    assert_eq!((1..=100).lob().percentile(0.55), Some(55));
+}
+
+#[test]
+fn percentile_every_whole_percent() {
+    for k in 1..=100 {
+        let p = f64::from(k) / 100.0;
+        assert_eq!((1..=100).lob().percentile(p), Some(k), "p = {p}");
+    }
+}
+
+#[test]
+fn percentile_bounds() {
+    assert_eq!((1..=10).lob().percentile(0.0), Some(1));
+    assert_eq!((1..=10).lob().percentile(1.0), Some(10));
+    assert_eq!((1..=10).lob().percentile(1.5), None);
+    assert_eq!((1..=10).lob().percentile(-0.1), None);
+    assert_eq!(Vec::<f64>::new().into_iter().lob().median(), None);
+}
+
+#[test]
+fn reservoir_sample_seeded_is_deterministic() {
+    let a = (0..10_000).lob().reservoir_sample_seeded(8, 7);
+    let b = (0..10_000).lob().reservoir_sample_seeded(8, 7);
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 8);
+
+    let mut unique = a.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), 8);
+    assert_ne!(a, (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn reservoir_sample_larger_than_stream_returns_all() {
+    let sample = (0..5).lob().reservoir_sample_seeded(10, 1);
+    assert_eq!(sample, vec![0, 1, 2, 3, 4]);
+    assert_eq!((0..5).lob().reservoir_sample(10), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn reservoir_sample_zero() {
+    assert!((0..5).lob().reservoir_sample(0).is_empty());
+}
+
+#[test]
+fn reservoir_sample_is_roughly_uniform() {
+    // Each of 10 values should be picked about 3000 / 10 * 3 = 900 times
+    let mut hits = [0usize; 10];
+    for seed in 0..3_000 {
+        for x in (0..10).lob().reservoir_sample_seeded(3, seed) {
+            hits[x] += 1;
+        }
+    }
+    assert!(hits.iter().all(|&h| (750..1_050).contains(&h)), "{hits:?}");
+}
+
+#[test]
+fn collect_result_all_ok() {
+    let result: Result<Vec<i32>, String> = (1..=3).lob().map(Ok).collect_result();
+    assert_eq!(result, Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn collect_result_short_circuits_on_error() {
+    let mut visited = Vec::new();
+    let result = vec!["1", "x", "3", "y"]
+        .into_iter()
+        .inspect(|s| visited.push(*s))
+        .lob()
+        .map(|s| s.parse::<i32>().map_err(|_| format!("bad: {s}")))
+        .collect_result();
+    assert_eq!(result, Err("bad: x".to_string()));
+    assert_eq!(visited, vec!["1", "x"]);
+}
+
+#[test]
+fn try_map_all_ok() {
+    let result = vec!["1", "2", "3"]
+        .into_iter()
+        .lob()
+        .try_map(|s| s.parse::<i32>());
+    assert_eq!(result, Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn try_map_stops_at_third_element() {
+    let mut visited = Vec::new();
+    let result = vec!["1", "2", "x", "4"].into_iter().lob().try_map(|s| {
+        visited.push(s);
+        s.parse::<i32>().map_err(|_| format!("bad: {s}"))
+    });
+    assert_eq!(result, Err("bad: x".to_string()));
+    assert_eq!(visited, vec!["1", "2", "x"]);
+}
+
+#[test]
+fn concat_basic() {
+    let flat = vec![vec![1, 2], vec![3]].into_iter().lob().concat();
+    assert_eq!(flat, vec![1, 2, 3]);
+}
+
+#[test]
+fn concat_strings_and_empties() {
+    let flat: Vec<char> = vec!["ab", "", "c"]
+        .into_iter()
+        .lob()
+        .map(|s| s.chars().collect::<Vec<_>>())
+        .concat();
+    assert_eq!(flat, vec!['a', 'b', 'c']);
+}
@@ -1,6 +1,6 @@
 //! Comprehensive tests for transformation operations
 
-use lob_core::LobExt;
+use lob_core::{LobExt, Position};
 
 #[test]
 fn map_basic() {
@@ -108,6 +108,15 @@ fn flatten_empty_inner() {
     assert_eq!(result, vec![1, 2, 3]);
 }
 
+#[test]
+fn flatten_removes_one_level_per_call() {
+    let nested = vec![vec![vec![1, 2], vec![3]], vec![vec![4]]];
+    let once: Vec<Vec<i32>> = nested.clone().into_iter().lob().flatten().collect();
+    assert_eq!(once, vec![vec![1, 2], vec![3], vec![4]]);
+    let twice: Vec<i32> = nested.into_iter().lob().flatten().flatten().collect();
+    assert_eq!(twice, vec![1, 2, 3, 4]);
+}
+
 #[test]
 fn flatten_all_empty() {
     let result: Vec<i32> = vec![vec![], vec![], vec![]]
@@ -128,3 +137,376 @@ fn chained_transformations() {
         .collect();
     assert_eq!(result, vec![(0, 1), (1, 3), (2, 5), (3, 7), (4, 9)]);
 }
+
+#[test]
+fn running_median_intermediate_values() {
+    let result: Vec<_> = vec![2, 8, 4, 6, 1, 9]
+        .into_iter()
+        .lob()
+        .running_median()
+        .collect();
+    assert_eq!(result, vec![2.0, 5.0, 4.0, 5.0, 4.0, 5.0]);
+}
+
+#[test]
+fn running_median_final_matches_batch() {
+    let data = vec![7.5, -1.0, 3.25, 10.0, 3.25, 0.5, 8.0];
+
+    let mut sorted = data.clone();
+    sorted.sort_by(f64::total_cmp);
+    let batch_median = sorted[sorted.len() / 2];
+
+    let last = data.into_iter().lob().running_median().last();
+    assert_eq!(last, Some(batch_median));
+}
+
+#[test]
+fn running_median_empty() {
+    let result: Vec<f64> = Vec::<i32>::new()
+        .into_iter()
+        .lob()
+        .running_median()
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn with_watermark_never_decreases() {
+    let timestamps = vec![10, 12, 11, 15, 9, 15, 20, 18];
+    let result: Vec<_> = timestamps
+        .clone()
+        .into_iter()
+        .lob()
+        .with_watermark(|t| *t)
+        .collect();
+
+    let items: Vec<_> = result.iter().map(|(t, _)| *t).collect();
+    assert_eq!(items, timestamps);
+
+    let marks: Vec<_> = result.iter().map(|(_, w)| *w).collect();
+    assert_eq!(marks, vec![10, 12, 12, 15, 15, 15, 20, 20]);
+    assert!(marks.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn with_watermark_projection() {
+    let events = vec![("a", 2.5), ("b", 1.0), ("c", 3.0)];
+    let marks: Vec<f64> = events
+        .into_iter()
+        .lob()
+        .with_watermark(|(_, ts)| *ts)
+        .map(|(_, w)| w)
+        .collect();
+    assert_eq!(marks, vec![2.5, 2.5, 3.0]);
+}
+
+#[test]
+fn tee_drain_one_branch_first() {
+    let (a, b) = vec![1, 2, 3].into_iter().lob().tee();
+    let first: Vec<_> = a.collect();
+    let second: Vec<_> = b.collect();
+    assert_eq!(first, vec![1, 2, 3]);
+    assert_eq!(second, vec![1, 2, 3]);
+}
+
+#[test]
+fn tee_interleaved() {
+    let (mut a, mut b) = (0..4).lob().tee();
+    assert_eq!(a.next(), Some(0));
+    assert_eq!(a.next(), Some(1));
+    assert_eq!(b.next(), Some(0));
+    assert_eq!(b.next(), Some(1));
+    assert_eq!(b.next(), Some(2));
+    assert_eq!(a.next(), Some(2));
+    assert_eq!(a.next(), Some(3));
+    assert_eq!(a.next(), None);
+    assert_eq!(b.next(), Some(3));
+    assert_eq!(b.next(), None);
+}
+
+#[test]
+fn tee_two_aggregates() {
+    let (a, b) = vec![2, 4, 6].into_iter().lob().tee();
+    assert_eq!(a.count(), 3);
+    assert_eq!(b.sum::<i32>(), 12);
+}
+
+#[test]
+fn map_while_stops_at_first_none() {
+    let result: Vec<i32> = vec!["1", "2", "x", "3"]
+        .into_iter()
+        .lob()
+        .map_while(|s| s.parse::<i32>().ok())
+        .collect();
+    assert_eq!(result, vec![1, 2]);
+}
+
+#[test]
+fn map_while_all_some() {
+    let result: Vec<_> = (1..=3).lob().map_while(|x| Some(x * 2)).collect();
+    assert_eq!(result, vec![2, 4, 6]);
+}
+
+#[test]
+fn intersperse_basic() {
+    let result: Vec<_> = vec![1, 2, 3].into_iter().lob().intersperse(0).collect();
+    assert_eq!(result, vec![1, 0, 2, 0, 3]);
+}
+
+#[test]
+fn intersperse_single() {
+    let result: Vec<_> = vec![1].into_iter().lob().intersperse(0).collect();
+    assert_eq!(result, vec![1]);
+}
+
+#[test]
+fn intersperse_empty() {
+    let result: Vec<i32> = Vec::new().into_iter().lob().intersperse(0).collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn flatten_opt_mixed() {
+    let result: Vec<_> = vec![None, Some("a"), None, Some("b")]
+        .into_iter()
+        .lob()
+        .flatten_opt()
+        .collect();
+    assert_eq!(result, vec!["a", "b"]);
+}
+
+#[test]
+fn flatten_ok_mixed() {
+    let input: Vec<Result<i32, String>> = vec![Ok(1), Err("bad".to_string()), Ok(3)];
+    let result: Vec<_> = input.into_iter().lob().flatten_ok().collect();
+    assert_eq!(result, vec![1, 3]);
+}
+
+#[test]
+fn flatten_ok_parse() {
+    let result: Vec<i32> = vec!["10", "x", "-2"]
+        .into_iter()
+        .lob()
+        .map(str::parse::<i32>)
+        .flatten_ok()
+        .collect();
+    assert_eq!(result, vec![10, -2]);
+}
+
+#[test]
+fn enumerate_from_one() {
+    let result: Vec<_> = vec!["a", "b"].into_iter().lob().enumerate_from(1).collect();
+    assert_eq!(result, vec![(1, "a"), (2, "b")]);
+}
+
+#[test]
+fn zip_longest_left_longer() {
+    let result: Vec<_> = vec![1, 2, 3]
+        .into_iter()
+        .lob()
+        .zip_longest(vec!["a"])
+        .collect();
+    assert_eq!(
+        result,
+        vec![(Some(1), Some("a")), (Some(2), None), (Some(3), None)]
+    );
+}
+
+#[test]
+fn zip_longest_right_longer() {
+    let result: Vec<_> = vec![1]
+        .into_iter()
+        .lob()
+        .zip_longest(vec!['x', 'y'])
+        .collect();
+    assert_eq!(result, vec![(Some(1), Some('x')), (None, Some('y'))]);
+}
+
+#[test]
+fn zip_longest_size_hint() {
+    let lob = (0..3).lob().zip_longest(0..5);
+    assert_eq!(lob.size_hint(), (5, Some(5)));
+}
+
+#[test]
+fn with_position_single() {
+    let result: Vec<_> = vec!['x'].into_iter().lob().with_position().collect();
+    assert_eq!(result, vec![(Position::Only, 'x')]);
+}
+
+#[test]
+fn with_position_two() {
+    let result: Vec<_> = vec![1, 2].into_iter().lob().with_position().collect();
+    assert_eq!(result, vec![(Position::First, 1), (Position::Last, 2)]);
+}
+
+#[test]
+fn with_position_three() {
+    let result: Vec<_> = (1..=3).lob().with_position().collect();
+    assert_eq!(
+        result,
+        vec![
+            (Position::First, 1),
+            (Position::Middle, 2),
+            (Position::Last, 3)
+        ]
+    );
+}
+
+#[test]
+fn with_position_empty() {
+    let result: Vec<(Position, i32)> = Vec::new().into_iter().lob().with_position().collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn tap_runs_once_and_passes_through() {
+    let mut calls = 0;
+    let mut seen_hint = (0, None);
+    let result: Vec<_> = vec![1, 2, 3]
+        .into_iter()
+        .lob()
+        .tap(|l| {
+            calls += 1;
+            seen_hint = l.size_hint();
+        })
+        .map(|x| x * 10)
+        .collect();
+    assert_eq!(calls, 1);
+    assert_eq!(seen_hint, (3, Some(3)));
+    assert_eq!(result, vec![10, 20, 30]);
+}
+
+#[test]
+fn tap_can_peek_by_cloning() {
+    let mut peeked = Vec::new();
+    let result = (1..=3)
+        .lob()
+        .tap(|l| peeked = l.clone().to_list())
+        .to_list();
+    assert_eq!(peeked, result);
+}
+
+#[test]
+fn cycle_take() {
+    let result = vec![1, 2].into_iter().lob().cycle().take(5).to_list();
+    assert_eq!(result, vec![1, 2, 1, 2, 1]);
+}
+
+#[test]
+fn cycle_empty_is_empty() {
+    let result = Vec::<i32>::new()
+        .into_iter()
+        .lob()
+        .cycle()
+        .take(3)
+        .to_list();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn sorted_by_descending() {
+    let result: Vec<_> = vec![3, 1, 2]
+        .into_iter()
+        .lob()
+        .sorted_by(|a, b| b.cmp(a))
+        .collect();
+    assert_eq!(result, vec![3, 2, 1]);
+}
+
+#[test]
+fn sorted_by_floats_ascending() {
+    let result: Vec<f64> = vec![2.5, -1.0, 3.25, 0.0]
+        .into_iter()
+        .lob()
+        .sorted_by(f64::total_cmp)
+        .collect();
+    assert_eq!(result, vec![-1.0, 0.0, 2.5, 3.25]);
+}
+
+#[test]
+fn sorted_by_is_stable() {
+    let result: Vec<_> = vec![(1, "a"), (0, "b"), (1, "c"), (0, "d")]
+        .into_iter()
+        .lob()
+        .sorted_by(|x, y| x.0.cmp(&y.0))
+        .collect();
+    assert_eq!(result, vec![(0, "b"), (0, "d"), (1, "a"), (1, "c")]);
+}
+
+#[test]
+fn shuffle_seeded_permutation() {
+    let result: Vec<_> = (0..5).lob().shuffle_seeded(42).collect();
+    assert_eq!(result, vec![1, 2, 4, 0, 3]);
+}
+
+#[test]
+fn shuffle_preserves_elements() {
+    let input = vec![3, 1, 3, 2, 1, 5];
+    let mut seeded: Vec<_> = input.clone().into_iter().lob().shuffle_seeded(9).collect();
+    let mut unseeded: Vec<_> = input.clone().into_iter().lob().shuffle().collect();
+    let mut expected = input;
+    expected.sort_unstable();
+    seeded.sort_unstable();
+    unseeded.sort_unstable();
+    assert_eq!(seeded, expected);
+    assert_eq!(unseeded, expected);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_map_matches_sequential_map() {
+    let f = |x: u64| (0..x % 100).map(|i| i * x).sum::<u64>();
+    let sequential: Vec<_> = (0..10_000u64).lob().map(f).collect();
+    let parallel: Vec<_> = (0..10_000u64).lob().par_map(f).collect();
+    assert_eq!(parallel, sequential);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_map_empty() {
+    let result: Vec<i32> = Vec::<i32>::new()
+        .into_iter()
+        .lob()
+        .par_map(|x| x + 1)
+        .collect();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn materialize_can_be_cloned_and_reiterated() {
+    let m = (1..=5).lob().filter(|x| x % 2 == 1).materialize();
+    let first: Vec<i32> = m.clone().collect();
+    let second: Vec<i32> = m.collect();
+    assert_eq!(first, vec![1, 3, 5]);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn materialize_branches_into_terminals() {
+    let m = vec!["a", "bb", "ccc"]
+        .into_iter()
+        .lob()
+        .map(str::len)
+        .materialize();
+    let total: usize = m.clone().sum();
+    assert_eq!(total, 6);
+    assert_eq!(m.count(), 3);
+}
+
+#[test]
+fn default_if_empty_passes_non_empty_through() {
+    let result: Vec<_> = vec![1, 2, 3]
+        .into_iter()
+        .lob()
+        .default_if_empty(0)
+        .collect();
+    assert_eq!(result, vec![1, 2, 3]);
+}
+
+#[test]
+fn default_if_empty_yields_default_once() {
+    let empty: Vec<&str> = vec![];
+    let result: Vec<_> = empty.into_iter().lob().default_if_empty("none").collect();
+    assert_eq!(result, vec!["none"]);
+}
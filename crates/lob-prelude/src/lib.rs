@@ -7,23 +7,35 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Read};
 
 // Re-export core types and traits
-pub use lob_core::{HashSet, Lob, LobExt};
+pub use lob_core::{HashSet, Lob, LobExt, Position};
+
+#[cfg(feature = "columnar")]
+pub use lob_core::{ColumnBatch, ColumnValue, IntoRow};
+
+#[cfg(feature = "parquet")]
+mod parquet_output;
+#[cfg(feature = "parquet")]
+pub use parquet_output::{output_parquet, write_parquet};
 
 // Re-export serde_json for JSON output
 pub use serde_json;
 
+// Re-export serde_yaml for YAML output
+pub use serde_yaml;
+
 // Re-export tabled for table output
 pub use tabled;
 
 /// Creates a Lob iterator from stdin lines
 ///
 /// This function reads lines from stdin and returns a `Lob` iterator over them.
-/// Lines are trimmed and empty lines are filtered out by default.
+/// Lines are trimmed and empty lines are filtered out by default. Gzip-compressed
+/// input is detected and decompressed transparently.
 ///
 /// # Examples
 ///
@@ -37,10 +49,13 @@ pub use tabled;
 /// ```
 #[must_use]
 pub fn input() -> Lob<impl Iterator<Item = String>> {
-    let stdin = io::stdin();
+    lines_from_reader(maybe_gunzip(io::stdin().lock()))
+}
+
+/// Trimmed, non-empty lines of a reader
+fn lines_from_reader<R: BufRead>(reader: R) -> Lob<impl Iterator<Item = String>> {
     Lob::new(
-        stdin
-            .lock()
+        reader
             .lines()
             .map_while(Result::ok)
             .map(|s| s.trim().to_string())
@@ -48,6 +63,124 @@ pub fn input() -> Lob<impl Iterator<Item = String>> {
     )
 }
 
+/// Wrap a reader in a gzip decoder if it starts with the gzip magic bytes
+///
+/// Concatenated gzip members (as produced by `cat a.gz b.gz`) are all read.
+fn maybe_gunzip<R: BufRead + 'static>(mut reader: R) -> Box<dyn BufRead> {
+    let is_gzip = reader
+        .fill_buf()
+        .is_ok_and(|buf| buf.starts_with(&[0x1f, 0x8b]));
+    if is_gzip {
+        Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    }
+}
+
+/// Creates a Lob iterator over the raw bytes of stdin
+///
+/// # Examples
+///
+/// ```no_run
+/// use lob_prelude::*;
+///
+/// // Count newline bytes
+/// let newlines = input_bytes().filter(|b| *b == b'\n').count();
+/// ```
+#[must_use]
+pub fn input_bytes() -> Lob<impl Iterator<Item = u8>> {
+    bytes_from_reader(io::stdin().lock())
+}
+
+/// Creates a Lob iterator over the characters of stdin
+///
+/// Input is decoded as UTF-8 lossily: each invalid byte sequence becomes a
+/// single U+FFFD replacement character instead of ending the stream, and an
+/// incomplete sequence at the very end is replaced the same way.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lob_prelude::*;
+///
+/// // Count letters
+/// let letters = input_chars().filter(|c| c.is_alphabetic()).count();
+/// ```
+#[must_use]
+pub fn input_chars() -> Lob<impl Iterator<Item = char>> {
+    chars_from_reader(io::stdin().lock())
+}
+
+fn bytes_from_reader<R: Read>(reader: R) -> Lob<impl Iterator<Item = u8>> {
+    Lob::new(BufReader::new(reader).bytes().map_while(Result::ok))
+}
+
+fn chars_from_reader<R: Read>(reader: R) -> Lob<impl Iterator<Item = char>> {
+    Lob::new(LossyChars {
+        reader,
+        buf: Vec::new(),
+        pending: VecDeque::new(),
+        eof: false,
+    })
+}
+
+/// Streaming lossy UTF-8 decoder over a reader
+struct LossyChars<R> {
+    reader: R,
+    buf: Vec<u8>,
+    pending: VecDeque<char>,
+    eof: bool,
+}
+
+impl<R: Read> Iterator for LossyChars<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(c) = self.pending.pop_front() {
+                return Some(c);
+            }
+            if self.eof && self.buf.is_empty() {
+                return None;
+            }
+
+            if !self.eof {
+                let mut chunk = [0u8; 8192];
+                match self.reader.read(&mut chunk) {
+                    Ok(0) | Err(_) => self.eof = true,
+                    Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+
+            match std::str::from_utf8(&self.buf) {
+                Ok(text) => {
+                    self.pending.extend(text.chars());
+                    self.buf.clear();
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    let text = std::str::from_utf8(&self.buf[..valid]).unwrap_or_default();
+                    self.pending.extend(text.chars());
+                    match e.error_len() {
+                        Some(len) => {
+                            self.pending.push_back(char::REPLACEMENT_CHARACTER);
+                            self.buf.drain(..valid + len);
+                        }
+                        // Truncated sequence: wait for more input unless at the end
+                        None if self.eof => {
+                            self.pending.push_back(char::REPLACEMENT_CHARACTER);
+                            self.buf.clear();
+                        }
+                        None => {
+                            self.buf.drain(..valid);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Creates a Lob iterator from any iterable
 ///
 /// This is a convenience function to convert any type that implements
@@ -87,25 +220,118 @@ pub fn range(start: i64, end: i64) -> Lob<impl Iterator<Item = i64>> {
     Lob::new(start..end)
 }
 
+/// Creates a Lob iterator from `start` towards `end` (exclusive) by `step`
+///
+/// A negative `step` counts down. If `step` points away from `end` the
+/// range is empty.
+///
+/// # Panics
+///
+/// Panics if `step` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(range_step(0, 10, 3).to_list(), vec![0, 3, 6, 9]);
+/// assert_eq!(range_step(5, 0, -2).to_list(), vec![5, 3, 1]);
+/// assert!(range_step(0, 5, -1).to_list().is_empty());
+/// ```
+#[must_use]
+pub fn range_step(start: i64, end: i64, step: i64) -> Lob<impl Iterator<Item = i64>> {
+    assert!(step != 0, "range step must not be 0");
+    let mut next = Some(start);
+    Lob::new(std::iter::from_fn(move || {
+        let current = next?;
+        let in_range = if step > 0 {
+            current < end
+        } else {
+            current > end
+        };
+        if !in_range {
+            next = None;
+            return None;
+        }
+        next = current.checked_add(step);
+        Some(current)
+    }))
+}
+
+/// Creates a Lob iterator over `start..=end`
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(range_inclusive(1, 3).to_list(), vec![1, 2, 3]);
+/// ```
+#[must_use]
+pub fn range_inclusive(start: i64, end: i64) -> Lob<impl Iterator<Item = i64>> {
+    Lob::new(start..=end)
+}
+
+/// Creates an infinite Lob iterator repeating `value`
+///
+/// The stream never ends on its own; bound it with `take` or another
+/// short-circuiting operation.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(repeat(0).take(3).to_list(), vec![0, 0, 0]);
+/// ```
+#[must_use]
+pub fn repeat<T: Clone>(value: T) -> Lob<impl Iterator<Item = T>> {
+    Lob::new(std::iter::repeat(value))
+}
+
+/// Creates a Lob iterator yielding `n` copies of `value`
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(replicate("x", 3).to_list(), vec!["x", "x", "x"]);
+/// ```
+#[must_use]
+pub fn replicate<T: Clone>(value: T, n: usize) -> Lob<impl Iterator<Item = T>> {
+    Lob::new(std::iter::repeat_n(value, n))
+}
+
 // File input helpers
 
-/// Read lines from multiple files
+/// Environment variable through which `lob` passes input file paths to the
+/// compiled pipeline, as an OS path list (see [`std::env::join_paths`])
+pub const INPUT_FILES_ENV: &str = "LOB_INPUT_FILES";
+
+/// Input file paths handed over by `lob` in [`INPUT_FILES_ENV`]
+///
+/// Returns an empty list if the variable is unset.
+#[must_use]
+pub fn input_files() -> Vec<std::path::PathBuf> {
+    std::env::var_os(INPUT_FILES_ENV)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+/// Read lines from multiple files in order
+///
+/// Lines are trimmed and empty lines dropped, like [`input`]. A file that
+/// cannot be opened is skipped with a warning on stderr so one bad path does
+/// not abort the whole pipeline.
 #[must_use]
 #[allow(clippy::needless_collect)]
 pub fn input_from_files(paths: &[std::path::PathBuf]) -> Lob<impl Iterator<Item = String>> {
     let lines: Vec<String> = paths
         .iter()
         .flat_map(|path| {
-            File::open(path)
-                .ok()
-                .map(|file| {
-                    BufReader::new(file)
-                        .lines()
-                        .map_while(Result::ok)
-                        .map(|s| s.trim().to_string())
-                        .filter(|s| !s.is_empty())
-                        .collect::<Vec<_>>()
-                })
+            open_or_warn(path)
+                .map(|reader| lines_from_reader(reader).collect::<Vec<_>>())
                 .unwrap_or_default()
         })
         .collect();
@@ -113,14 +339,23 @@ pub fn input_from_files(paths: &[std::path::PathBuf]) -> Lob<impl Iterator<Item
     Lob::new(lines.into_iter())
 }
 
+/// Open a file for reading, or warn on stderr and return `None` if it can't be opened
+///
+/// Gzip-compressed files are decompressed transparently, so a mix of plain
+/// and `.gz` inputs can be read together.
+fn open_or_warn(path: &std::path::Path) -> Option<Box<dyn BufRead>> {
+    File::open(path)
+        .map(|file| maybe_gunzip(BufReader::new(file)))
+        .map_err(|e| eprintln!("lob: skipping {}: {e}", path.display()))
+        .ok()
+}
+
 // CSV input helpers
 
 /// Parse CSV from stdin with headers
 #[must_use]
 pub fn input_csv() -> Lob<impl Iterator<Item = HashMap<String, String>>> {
-    let stdin = io::stdin();
-    let reader = BufReader::new(stdin.lock());
-    parse_csv_reader(reader)
+    parse_csv_reader(maybe_gunzip(io::stdin().lock()))
 }
 
 /// Parse CSV from files with headers
@@ -132,12 +367,8 @@ pub fn input_csv_from_files(
     let rows: Vec<HashMap<String, String>> = paths
         .iter()
         .flat_map(|path| {
-            File::open(path)
-                .ok()
-                .map(|file| {
-                    let reader = BufReader::new(file);
-                    parse_csv_reader(reader).collect::<Vec<_>>()
-                })
+            open_or_warn(path)
+                .map(|reader| parse_csv_reader(reader).collect::<Vec<_>>())
                 .unwrap_or_default()
         })
         .collect();
@@ -146,23 +377,73 @@ pub fn input_csv_from_files(
 }
 
 fn parse_csv_reader<R: io::Read>(reader: R) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
-    let mut csv_reader = csv::Reader::from_reader(reader);
+    parse_table_reader(reader, b',', true)
+}
 
-    let headers: Vec<String> = csv_reader
-        .headers()
-        .ok()
-        .map(|h| h.iter().map(|s| s.to_string()).collect())
-        .unwrap_or_default();
+/// Parse delimited records into maps keyed by the header row, or by
+/// position (`col0`, `col1`, ...) when `has_header` is false
+fn parse_table_reader<R: io::Read>(
+    reader: R,
+    delimiter: u8,
+    has_header: bool,
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_header)
+        .from_reader(reader);
+
+    let headers: Vec<String> = if has_header {
+        csv_reader
+            .headers()
+            .ok()
+            .map(|h| h.iter().map(|s| s.to_string()).collect())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
 
     let rows: Vec<HashMap<String, String>> = csv_reader
         .records()
         .filter_map(Result::ok)
         .map(|record| {
-            let mut row = HashMap::new();
-            for (header, value) in headers.iter().zip(record.iter()) {
-                row.insert(header.clone(), value.to_string());
-            }
-            row
+            record
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| {
+                    let key = if has_header {
+                        headers.get(i)?.clone()
+                    } else {
+                        positional_key(i)
+                    };
+                    Some((key, value.to_string()))
+                })
+                .collect()
+        })
+        .collect();
+
+    Lob::new(rows.into_iter())
+}
+
+/// Parse CSV from stdin without a header row
+///
+/// Every line is data; columns are keyed `col0`, `col1`, ...
+#[must_use]
+pub fn input_csv_headerless() -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    parse_table_reader(maybe_gunzip(io::stdin().lock()), b',', false)
+}
+
+/// Parse CSV from files without a header row
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_csv_headerless_from_files(
+    paths: &[std::path::PathBuf],
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let rows: Vec<HashMap<String, String>> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| parse_table_reader(reader, b',', false).collect::<Vec<_>>())
+                .unwrap_or_default()
         })
         .collect();
 
@@ -174,9 +455,7 @@ fn parse_csv_reader<R: io::Read>(reader: R) -> Lob<impl Iterator<Item = HashMap<
 /// Parse TSV from stdin with headers
 #[must_use]
 pub fn input_tsv() -> Lob<impl Iterator<Item = HashMap<String, String>>> {
-    let stdin = io::stdin();
-    let reader = BufReader::new(stdin.lock());
-    parse_tsv_reader(reader)
+    parse_tsv_reader(maybe_gunzip(io::stdin().lock()))
 }
 
 /// Parse TSV from files with headers
@@ -188,12 +467,8 @@ pub fn input_tsv_from_files(
     let rows: Vec<HashMap<String, String>> = paths
         .iter()
         .flat_map(|path| {
-            File::open(path)
-                .ok()
-                .map(|file| {
-                    let reader = BufReader::new(file);
-                    parse_tsv_reader(reader).collect::<Vec<_>>()
-                })
+            open_or_warn(path)
+                .map(|reader| parse_tsv_reader(reader).collect::<Vec<_>>())
                 .unwrap_or_default()
         })
         .collect();
@@ -202,103 +477,750 @@ pub fn input_tsv_from_files(
 }
 
 fn parse_tsv_reader<R: io::Read>(reader: R) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .delimiter(b'\t')
-        .from_reader(reader);
+    parse_table_reader(reader, b'\t', true)
+}
 
-    let headers: Vec<String> = csv_reader
-        .headers()
-        .ok()
-        .map(|h| h.iter().map(|s| s.to_string()).collect())
-        .unwrap_or_default();
+/// Parse TSV from stdin without a header row
+///
+/// Every line is data; columns are keyed `col0`, `col1`, ...
+#[must_use]
+pub fn input_tsv_headerless() -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    parse_table_reader(maybe_gunzip(io::stdin().lock()), b'\t', false)
+}
 
-    let rows: Vec<HashMap<String, String>> = csv_reader
-        .records()
-        .filter_map(Result::ok)
-        .map(|record| {
-            let mut row = HashMap::new();
-            for (header, value) in headers.iter().zip(record.iter()) {
-                row.insert(header.clone(), value.to_string());
-            }
-            row
+/// Parse TSV from files without a header row
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_tsv_headerless_from_files(
+    paths: &[std::path::PathBuf],
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let rows: Vec<HashMap<String, String>> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| parse_table_reader(reader, b'\t', false).collect::<Vec<_>>())
+                .unwrap_or_default()
         })
         .collect();
 
     Lob::new(rows.into_iter())
 }
 
-// JSON input helpers
+// Split-field input helpers
 
-/// Parse JSON lines from stdin
+/// Split a line into fields on `separator`
+///
+/// No quoting rules apply. Empty fields are kept: a leading or trailing
+/// separator yields an empty first or last field, and consecutive separators
+/// yield empty fields between them.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(split_fields("a b c", ' '), vec!["a", "b", "c"]);
+/// assert_eq!(split_fields(",a,,b,", ','), vec!["", "a", "", "b", ""]);
+/// ```
 #[must_use]
-pub fn input_json() -> Lob<impl Iterator<Item = serde_json::Value>> {
-    let stdin = io::stdin();
+pub fn split_fields(line: &str, separator: char) -> Vec<String> {
+    line.split(separator).map(str::to_string).collect()
+}
+
+/// Read stdin as lines split into fields on `separator`
+///
+/// Each non-empty line becomes a `Vec<String>` (see [`split_fields`]). Lines
+/// are not trimmed, so separators at either end produce empty fields.
+#[must_use]
+pub fn input_split(separator: char) -> Lob<impl Iterator<Item = Vec<String>>> {
+    split_reader(maybe_gunzip(io::stdin().lock()), separator)
+}
+
+/// Read files as lines split into fields on `separator`
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_split_from_files(
+    paths: &[std::path::PathBuf],
+    separator: char,
+) -> Lob<impl Iterator<Item = Vec<String>>> {
+    let rows: Vec<Vec<String>> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| split_reader(reader, separator).collect::<Vec<_>>())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    Lob::new(rows.into_iter())
+}
+
+/// Non-empty lines of a reader split into fields
+fn split_reader<R: BufRead>(reader: R, separator: char) -> Lob<impl Iterator<Item = Vec<String>>> {
     Lob::new(
-        stdin
-            .lock()
+        reader
             .lines()
             .map_while(Result::ok)
-            .filter_map(|line| serde_json::from_str(&line).ok()),
+            .filter(|line| !line.is_empty())
+            .map(move |line| split_fields(line.strip_suffix('\r').unwrap_or(&line), separator)),
     )
 }
 
-/// Parse JSON lines from files
+// Delimited and fixed-width input helpers
+
+/// Key for the column at `index` when the input has no header row
+fn positional_key(index: usize) -> String {
+    format!("col{index}")
+}
+
+/// Parse stdin split on a custom delimiter (e.g. `|` or `;`)
+///
+/// There is no header row: columns are keyed `col0`, `col1`, ... Quoted
+/// fields follow CSV rules, and rows may have different lengths.
+///
+/// # Panics
+///
+/// Panics if `delimiter` is not an ASCII character.
+#[must_use]
+pub fn input_delimited(delimiter: char) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    parse_delimited_reader(maybe_gunzip(io::stdin().lock()), delimiter)
+}
+
+/// Parse files split on a custom delimiter
+///
+/// # Panics
+///
+/// Panics if `delimiter` is not an ASCII character.
 #[must_use]
 #[allow(clippy::needless_collect)]
-pub fn input_json_from_files(
+pub fn input_delimited_from_files(
     paths: &[std::path::PathBuf],
-) -> Lob<impl Iterator<Item = serde_json::Value>> {
-    let values: Vec<serde_json::Value> = paths
+    delimiter: char,
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let rows: Vec<HashMap<String, String>> = paths
         .iter()
         .flat_map(|path| {
-            File::open(path)
-                .ok()
-                .map(|file| {
-                    BufReader::new(file)
-                        .lines()
-                        .map_while(Result::ok)
-                        .filter_map(|line| serde_json::from_str(&line).ok())
-                        .collect::<Vec<_>>()
-                })
+            open_or_warn(path)
+                .map(|reader| parse_delimited_reader(reader, delimiter).collect::<Vec<_>>())
                 .unwrap_or_default()
         })
         .collect();
 
-    Lob::new(values.into_iter())
+    Lob::new(rows.into_iter())
 }
 
-// CSV output helper
+fn parse_delimited_reader<R: io::Read>(
+    reader: R,
+    delimiter: char,
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let delimiter = u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .expect("delimiter must be an ASCII character");
+    let csv_reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
 
-/// Output data as CSV
-pub fn output_csv<T: serde::Serialize>(items: &[T]) {
-    if items.is_empty() {
-        return;
-    }
+    Lob::new(
+        csv_reader
+            .into_records()
+            .filter_map(Result::ok)
+            .map(|record| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| (positional_key(i), value.to_string()))
+                    .collect()
+            }),
+    )
+}
 
-    let mut writer = csv::Writer::from_writer(io::stdout());
+/// Parse stdin as fixed-width columns
+///
+/// `widths` gives each column's width in characters; columns are keyed
+/// `col0`, `col1`, ... and values are trimmed. See [`split_fixed_width`] for
+/// how short and long lines are handled.
+#[must_use]
+pub fn input_fixed_width(widths: &[usize]) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    parse_fixed_width_reader(maybe_gunzip(io::stdin().lock()), widths.to_vec())
+}
 
-    for item in items {
-        let _ = writer.serialize(item);
-    }
+/// Parse files as fixed-width columns
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_fixed_width_from_files(
+    paths: &[std::path::PathBuf],
+    widths: &[usize],
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    let rows: Vec<HashMap<String, String>> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| parse_fixed_width_reader(reader, widths.to_vec()).collect::<Vec<_>>())
+                .unwrap_or_default()
+        })
+        .collect();
 
-    let _ = writer.flush();
+    Lob::new(rows.into_iter())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn parse_fixed_width_reader<R: BufRead>(
+    reader: R,
+    widths: Vec<usize>,
+) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+    Lob::new(
+        reader
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .map(move |line| {
+                split_fixed_width(line.trim_end_matches('\r'), &widths)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| (positional_key(i), value))
+                    .collect()
+            }),
+    )
+}
 
-    #[test]
-    fn lob_from_vec() {
-        let result: Vec<_> = lob(vec![1, 2, 3, 4, 5]).filter(|x| x % 2 == 0).collect();
-        assert_eq!(result, vec![2, 4]);
-    }
+/// Split a line into trimmed fixed-width fields
+///
+/// Widths count characters, not bytes. A line shorter than the total width
+/// is padded: fields past its end are empty strings, so every row has one
+/// value per width. Characters beyond the total width are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::split_fixed_width;
+///
+/// assert_eq!(split_fixed_width("ann  31oslo", &[5, 2, 4]), vec!["ann", "31", "oslo"]);
+/// assert_eq!(split_fixed_width("bob", &[5, 2]), vec!["bob", ""]);
+/// ```
+#[must_use]
+pub fn split_fixed_width(line: &str, widths: &[usize]) -> Vec<String> {
+    let mut chars = line.chars();
+    widths
+        .iter()
+        .map(|&width| {
+            chars
+                .by_ref()
+                .take(width)
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
 
-    #[test]
-    fn range_basic() {
-        let result: Vec<_> = range(0, 5).collect();
-        assert_eq!(result, vec![0, 1, 2, 3, 4]);
-    }
+// JSON input helpers
+
+/// Parse JSON lines from stdin
+///
+/// Lines that are not valid JSON (including blank lines) are skipped
+/// silently. Use [`input_json_strict`] to see them as errors instead.
+#[must_use]
+pub fn input_json() -> Lob<impl Iterator<Item = serde_json::Value>> {
+    parse_json_reader(maybe_gunzip(io::stdin().lock()))
+}
+
+/// Parse JSON lines from files, skipping malformed lines
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_json_from_files(
+    paths: &[std::path::PathBuf],
+) -> Lob<impl Iterator<Item = serde_json::Value>> {
+    let values: Vec<serde_json::Value> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| parse_json_reader(reader).collect::<Vec<_>>())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    Lob::new(values.into_iter())
+}
+
+/// Parse JSON lines from stdin, yielding an `Err` for each malformed line
+///
+/// The error names the 1-based input line. Blank lines are still skipped.
+/// Combine with `collect_result()` to stop at the first bad line, or
+/// `filter_map(Result::err)` to list them.
+#[must_use]
+pub fn input_json_strict() -> Lob<impl Iterator<Item = Result<serde_json::Value, String>>> {
+    parse_json_reader_strict(maybe_gunzip(io::stdin().lock()))
+}
+
+/// Parse JSON lines from files, yielding an `Err` for each malformed line
+///
+/// Errors are prefixed with the file path.
+#[must_use]
+#[allow(clippy::needless_collect)]
+pub fn input_json_strict_from_files(
+    paths: &[std::path::PathBuf],
+) -> Lob<impl Iterator<Item = Result<serde_json::Value, String>>> {
+    let values: Vec<_> = paths
+        .iter()
+        .flat_map(|path| {
+            open_or_warn(path)
+                .map(|reader| {
+                    parse_json_reader_strict(reader)
+                        .map(|r| r.map_err(|e| format!("{}: {}", path.display(), e)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+
+    Lob::new(values.into_iter())
+}
+
+/// Parse JSON lines from a reader, skipping lines that are not valid JSON
+fn parse_json_reader<R: BufRead>(reader: R) -> Lob<impl Iterator<Item = serde_json::Value>> {
+    Lob::new(
+        reader
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok()),
+    )
+}
+
+/// Parse JSON lines from a reader, reporting malformed lines as errors
+fn parse_json_reader_strict<R: BufRead>(
+    reader: R,
+) -> Lob<impl Iterator<Item = Result<serde_json::Value, String>>> {
+    Lob::new(
+        reader
+            .lines()
+            .map_while(Result::ok)
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(&line).map_err(|e| format!("line {}: {}", i + 1, e))
+            }),
+    )
+}
+
+// JSON flattening helpers
+
+/// Flatten a nested JSON value into a single-level map with path keys
+///
+/// Object fields are joined with dots (`{"a":{"b":1}}` becomes `a.b`) and
+/// array elements are addressed by index in brackets (`{"a":[1,2]}` becomes
+/// `a[0]` and `a[1]`). Scalars and empty objects/arrays are kept as leaves.
+/// A scalar at the top level is stored under the empty key.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// let value = serde_json::json!({"a": {"b": 1}, "c": [true, null]});
+/// let flat = flatten_json(&value);
+///
+/// assert_eq!(flat["a.b"], serde_json::json!(1));
+/// assert_eq!(flat["c[0]"], serde_json::json!(true));
+/// assert_eq!(flat["c[1]"], serde_json::Value::Null);
+/// ```
+#[must_use]
+pub fn flatten_json(v: &serde_json::Value) -> HashMap<String, serde_json::Value> {
+    let mut out = HashMap::new();
+    flatten_json_into(v, String::new(), &mut out);
+    out
+}
+
+fn flatten_json_into(
+    v: &serde_json::Value,
+    prefix: String,
+    out: &mut HashMap<String, serde_json::Value>,
+) {
+    match v {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_json_into(child, path, out);
+            }
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_json_into(child, format!("{}[{}]", prefix, i), out);
+            }
+        }
+        _ => {
+            out.insert(prefix, v.clone());
+        }
+    }
+}
+
+/// Render a JSON leaf as a plain table/CSV cell
+fn json_cell(v: serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// JSON-specific operations on `Lob` streams of `serde_json::Value`
+pub trait LobJsonExt: Sized {
+    /// Flatten each JSON record into a `HashMap<String, String>` row
+    ///
+    /// Keys follow the [`flatten_json`] naming scheme. Strings are emitted
+    /// unquoted, `null` becomes an empty cell and other values use their JSON
+    /// text, so the rows work directly with `--format table` and `--format csv`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_prelude::*;
+    ///
+    /// let rows: Vec<_> = lob(vec![serde_json::json!({"user": {"name": "ann", "age": 30}})])
+    ///     .flatten_json_records()
+    ///     .collect();
+    ///
+    /// assert_eq!(rows[0]["user.name"], "ann");
+    /// assert_eq!(rows[0]["user.age"], "30");
+    /// ```
+    fn flatten_json_records(self) -> Lob<impl Iterator<Item = HashMap<String, String>>>;
+}
+
+impl<I> LobJsonExt for Lob<I>
+where
+    I: Iterator<Item = serde_json::Value>,
+{
+    fn flatten_json_records(self) -> Lob<impl Iterator<Item = HashMap<String, String>>> {
+        self.map(|v| {
+            flatten_json(&v)
+                .into_iter()
+                .map(|(k, v)| (k, json_cell(v)))
+                .collect()
+        })
+    }
+}
+
+// Schema helpers
+
+/// Declared type of a CSV column for [`apply_schema`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// Signed 64-bit integer
+    Int,
+    /// 64-bit float
+    Float,
+    /// Boolean (`true`/`false`, `1`/`0`, `yes`/`no`, case-insensitive)
+    Bool,
+    /// Text, kept as-is
+    String,
+}
+
+/// A field value coerced according to a [`FieldType`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum TypedValue {
+    /// Integer value
+    Int(i64),
+    /// Float value
+    Float(f64),
+    /// Boolean value
+    Bool(bool),
+    /// Text value
+    String(String),
+    /// Empty cell in a non-text column
+    Null,
+}
+
+/// A field that could not be coerced by [`apply_schema`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// A column declared in the schema is absent from the row
+    MissingField(String),
+    /// A cell could not be parsed as its declared type
+    InvalidValue {
+        /// Column name
+        field: String,
+        /// Raw cell contents
+        value: String,
+        /// Declared type
+        expected: FieldType,
+    },
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing field `{}`", field),
+            Self::InvalidValue {
+                field,
+                value,
+                expected,
+            } => write!(
+                f,
+                "field `{}`: cannot parse {:?} as {:?}",
+                field, value, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Coerce a CSV row into typed values according to a schema
+///
+/// Coercion policy:
+/// - Columns not listed in the schema are kept as [`TypedValue::String`].
+/// - An empty (or all-whitespace) cell in a non-text column becomes [`TypedValue::Null`].
+/// - A schema column missing from the row, or a cell that does not parse as
+///   its declared type, fails the whole row with a [`SchemaError`]. Follow a
+///   `map` with `flatten()` to drop such rows, or `unwrap()` to abort.
+///
+/// # Errors
+///
+/// Returns the first [`SchemaError`] encountered, in schema order.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+/// use std::collections::HashMap;
+///
+/// let row: HashMap<String, String> =
+///     [("age".to_string(), "30".to_string())].into_iter().collect();
+/// let typed = apply_schema(&row, &[("age", FieldType::Int)]).unwrap();
+///
+/// assert_eq!(typed["age"], TypedValue::Int(30));
+/// ```
+pub fn apply_schema<S: std::hash::BuildHasher>(
+    row: &HashMap<String, String, S>,
+    schema: &[(&str, FieldType)],
+) -> Result<HashMap<String, TypedValue>, SchemaError> {
+    let mut typed: HashMap<String, TypedValue> = row
+        .iter()
+        .map(|(k, v)| (k.clone(), TypedValue::String(v.clone())))
+        .collect();
+
+    for (field, field_type) in schema {
+        let raw = row
+            .get(*field)
+            .ok_or_else(|| SchemaError::MissingField((*field).to_string()))?;
+        typed.insert((*field).to_string(), coerce(field, raw, *field_type)?);
+    }
+
+    Ok(typed)
+}
+
+fn coerce(field: &str, raw: &str, field_type: FieldType) -> Result<TypedValue, SchemaError> {
+    let cell = raw.trim();
+    if cell.is_empty() && field_type != FieldType::String {
+        return Ok(TypedValue::Null);
+    }
+
+    let parsed = match field_type {
+        FieldType::Int => cell.parse().ok().map(TypedValue::Int),
+        FieldType::Float => cell.parse().ok().map(TypedValue::Float),
+        FieldType::Bool => match cell.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(TypedValue::Bool(true)),
+            "false" | "0" | "no" => Some(TypedValue::Bool(false)),
+            _ => None,
+        },
+        FieldType::String => Some(TypedValue::String(raw.to_string())),
+    };
+
+    parsed.ok_or_else(|| SchemaError::InvalidValue {
+        field: field.to_string(),
+        value: raw.to_string(),
+        expected: field_type,
+    })
+}
+
+// Key/value helpers
+
+/// Split a `key<sep>value` line into a trimmed `(key, value)` pair
+///
+/// Only the first separator splits, so values may contain the separator
+/// themselves (`KEY=a=b` gives `("KEY", "a=b")`). Returns `None` for lines
+/// without a separator.
+///
+/// # Examples
+///
+/// ```
+/// use lob_prelude::*;
+///
+/// assert_eq!(
+///     parse_kv("HOME = /root", '='),
+///     Some(("HOME".to_string(), "/root".to_string()))
+/// );
+/// assert_eq!(parse_kv("no separator", '='), None);
+/// ```
+#[must_use]
+pub fn parse_kv(line: &str, sep: char) -> Option<(String, String)> {
+    line.split_once(sep)
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+}
+
+/// Text-specific operations on `Lob` streams of strings
+pub trait LobTextExt: Sized {
+    /// Collect `key<sep>value` lines into a map
+    ///
+    /// Lines are split with [`parse_kv`]; lines without the separator are
+    /// skipped, and a repeated key keeps its last value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lob_prelude::*;
+    ///
+    /// let env = lob(vec!["USER=ann", "# comment", "PATH=/bin"]).parse_kv_map('=');
+    ///
+    /// assert_eq!(env.len(), 2);
+    /// assert_eq!(env["USER"], "ann");
+    /// ```
+    fn parse_kv_map(self, sep: char) -> HashMap<String, String>;
+}
+
+impl<I> LobTextExt for Lob<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    fn parse_kv_map(self, sep: char) -> HashMap<String, String> {
+        self.into_iter()
+            .filter_map(|line| parse_kv(line.as_ref(), sep))
+            .collect()
+    }
+}
+
+// CSV output helper
+
+/// Output data as CSV
+///
+/// See [`write_csv`] for how rows are laid out.
+pub fn output_csv<T: serde::Serialize>(items: &[T]) {
+    let _ = write_csv(items, io::stdout().lock());
+}
+
+/// Write items as CSV with a header row
+///
+/// When every item serializes to a map or struct, the header is the sorted
+/// union of all keys (matching the table output) and missing cells are left
+/// blank, so rows with differing keys still line up. Other items are written
+/// as-is with the `csv` crate's serializer. Empty input writes nothing.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the underlying writer fails.
+pub fn write_csv<T, W>(items: &[T], writer: W) -> csv::Result<()>
+where
+    T: serde::Serialize,
+    W: io::Write,
+{
+    let mut writer = csv::Writer::from_writer(writer);
+
+    let rows: Option<Vec<serde_json::Map<String, serde_json::Value>>> = items
+        .iter()
+        .map(|item| match serde_json::to_value(item) {
+            Ok(serde_json::Value::Object(map)) => Some(map),
+            _ => None,
+        })
+        .collect();
+
+    match rows {
+        Some(rows) if !rows.is_empty() => {
+            let headers: std::collections::BTreeSet<&str> = rows
+                .iter()
+                .flat_map(|row| row.keys())
+                .map(String::as_str)
+                .collect();
+            writer.write_record(&headers)?;
+            for row in &rows {
+                writer.write_record(headers.iter().map(|h| match row.get(*h) {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                }))?;
+            }
+        }
+        _ => {
+            for item in items {
+                writer.serialize(item)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lob_from_vec() {
+        let result: Vec<_> = lob(vec![1, 2, 3, 4, 5]).filter(|x| x % 2 == 0).collect();
+        assert_eq!(result, vec![2, 4]);
+    }
+
+    #[test]
+    fn range_basic() {
+        let result: Vec<_> = range(0, 5).collect();
+        assert_eq!(result, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn range_step_ascending() {
+        assert_eq!(range_step(0, 10, 3).to_list(), vec![0, 3, 6, 9]);
+        assert_eq!(range_step(0, 9, 3).to_list(), vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn range_step_descending() {
+        assert_eq!(range_step(10, 0, -4).to_list(), vec![10, 6, 2]);
+    }
+
+    #[test]
+    fn range_step_wrong_direction_is_empty() {
+        assert!(range_step(0, 5, -1).to_list().is_empty());
+        assert!(range_step(5, 0, 1).to_list().is_empty());
+    }
+
+    #[test]
+    fn range_step_near_overflow() {
+        assert_eq!(
+            range_step(i64::MAX - 2, i64::MAX, 5).to_list(),
+            vec![i64::MAX - 2]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "range step must not be 0")]
+    fn range_step_zero_panics() {
+        let _ = range_step(0, 5, 0);
+    }
+
+    #[test]
+    fn repeat_is_bounded_by_take() {
+        assert_eq!(repeat('a').take(4).collect::<String>(), "aaaa");
+    }
+
+    #[test]
+    fn replicate_copies() {
+        assert_eq!(replicate("x", 3).to_list(), vec!["x", "x", "x"]);
+        assert!(replicate(1, 0).to_list().is_empty());
+    }
+
+    #[test]
+    fn range_inclusive_includes_end() {
+        assert_eq!(range_inclusive(1, 3).to_list(), vec![1, 2, 3]);
+        assert_eq!(range_inclusive(3, 3).to_list(), vec![3]);
+        assert!(range_inclusive(4, 3).to_list().is_empty());
+    }
 
     #[test]
     fn chained_operations() {
@@ -348,6 +1270,29 @@ mod tests {
         assert_eq!(result[1].get("age"), Some(&"25".to_string()));
     }
 
+    #[test]
+    fn test_parse_csv_quoted_fields() {
+        use std::io::Cursor;
+        let data = "name,note\n\"Smith, Jo\",\"says \"\"hi\"\"\"\n";
+
+        let result: Vec<_> = parse_csv_reader(Cursor::new(data)).collect();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0]["name"], "Smith, Jo");
+        assert_eq!(result[0]["note"], "says \"hi\"");
+    }
+
+    #[test]
+    fn test_parse_json_lines_skips_invalid() {
+        use std::io::Cursor;
+        let data = "{\"a\": 1}\nnot json\n{\"a\": 2}\n";
+
+        let result: Vec<_> = parse_json_reader(Cursor::new(data)).collect();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1]["a"], 2);
+    }
+
     #[test]
     fn test_input_from_files_basic() {
         use std::env;
@@ -370,6 +1315,25 @@ mod tests {
         let _ = fs::remove_file(&file2);
     }
 
+    #[test]
+    fn test_input_from_files_skips_missing() {
+        use std::env;
+        use std::fs;
+
+        let temp_dir = env::temp_dir();
+        let present = temp_dir.join("test_input_present.txt");
+        let missing = temp_dir.join("test_input_missing_does_not_exist.txt");
+        let _ = fs::remove_file(&missing);
+
+        fs::write(&present, "kept\n\n  padded  \n").unwrap();
+
+        let result: Vec<_> = input_from_files(&[missing, present.clone()]).collect();
+
+        assert_eq!(result, vec!["kept", "padded"]);
+
+        let _ = fs::remove_file(&present);
+    }
+
     #[test]
     fn test_input_csv_from_files() {
         use std::env;
@@ -424,4 +1388,476 @@ mod tests {
 
         let _ = fs::remove_file(&file);
     }
+
+    #[test]
+    fn test_flatten_json_nested_objects() {
+        let value = serde_json::json!({"a": {"b": 1, "c": {"d": "x"}}, "e": false});
+        let flat = flatten_json(&value);
+
+        assert_eq!(flat.len(), 3);
+        assert_eq!(flat["a.b"], serde_json::json!(1));
+        assert_eq!(flat["a.c.d"], serde_json::json!("x"));
+        assert_eq!(flat["e"], serde_json::json!(false));
+    }
+
+    #[test]
+    fn test_flatten_json_arrays() {
+        let value =
+            serde_json::json!({"tags": ["x", "y"], "items": [{"id": 1}, {"id": 2}], "none": []});
+        let flat = flatten_json(&value);
+
+        assert_eq!(flat["tags[0]"], serde_json::json!("x"));
+        assert_eq!(flat["tags[1]"], serde_json::json!("y"));
+        assert_eq!(flat["items[0].id"], serde_json::json!(1));
+        assert_eq!(flat["items[1].id"], serde_json::json!(2));
+        assert_eq!(flat["none"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_flatten_json_records() {
+        let rows: Vec<_> = lob(vec![
+            serde_json::json!({"a": {"b": "text"}, "n": null}),
+            serde_json::json!({"a": {"b": 2.5}}),
+        ])
+        .flatten_json_records()
+        .collect();
+
+        assert_eq!(rows[0]["a.b"], "text");
+        assert_eq!(rows[0]["n"], "");
+        assert_eq!(rows[1]["a.b"], "2.5");
+    }
+
+    #[test]
+    fn test_parse_kv_splits_on_first_separator() {
+        assert_eq!(
+            parse_kv("KEY=a=b", '='),
+            Some(("KEY".to_string(), "a=b".to_string()))
+        );
+        assert_eq!(
+            parse_kv("empty=", '='),
+            Some(("empty".to_string(), String::new()))
+        );
+        assert_eq!(parse_kv("missing", '='), None);
+    }
+
+    #[test]
+    fn test_parse_kv_map() {
+        let map = lob(vec![
+            "KEY=a=b".to_string(),
+            "skipped line".to_string(),
+            "other: 1".to_string(),
+            "NAME=lob".to_string(),
+        ])
+        .parse_kv_map('=');
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["KEY"], "a=b");
+        assert_eq!(map["NAME"], "lob");
+    }
+
+    fn row(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_schema_coerces_types() {
+        let record = row(&[
+            ("id", "7"),
+            ("score", "9.5"),
+            ("active", "TRUE"),
+            ("name", "ann"),
+        ]);
+        let schema = [
+            ("id", FieldType::Int),
+            ("score", FieldType::Float),
+            ("active", FieldType::Bool),
+        ];
+
+        let typed = apply_schema(&record, &schema).unwrap();
+
+        assert_eq!(typed["id"], TypedValue::Int(7));
+        assert_eq!(typed["score"], TypedValue::Float(9.5));
+        assert_eq!(typed["active"], TypedValue::Bool(true));
+        // Columns outside the schema pass through as text
+        assert_eq!(typed["name"], TypedValue::String("ann".to_string()));
+    }
+
+    #[test]
+    fn test_apply_schema_malformed_number() {
+        let record = row(&[("id", "seven")]);
+        let err = apply_schema(&record, &[("id", FieldType::Int)]).unwrap_err();
+
+        assert_eq!(
+            err,
+            SchemaError::InvalidValue {
+                field: "id".to_string(),
+                value: "seven".to_string(),
+                expected: FieldType::Int,
+            }
+        );
+        assert!(err.to_string().contains("seven"));
+    }
+
+    #[test]
+    fn test_apply_schema_empty_and_missing() {
+        let record = row(&[("score", "")]);
+
+        let typed = apply_schema(&record, &[("score", FieldType::Float)]).unwrap();
+        assert_eq!(typed["score"], TypedValue::Null);
+
+        let err = apply_schema(&record, &[("id", FieldType::Int)]).unwrap_err();
+        assert_eq!(err, SchemaError::MissingField("id".to_string()));
+    }
+
+    #[test]
+    fn test_bytes_from_reader() {
+        let bytes: Vec<u8> = bytes_from_reader(&b"ab\n\xff"[..]).collect();
+        assert_eq!(bytes, vec![b'a', b'b', b'\n', 0xff]);
+    }
+
+    #[test]
+    fn test_chars_from_reader_utf8() {
+        let chars: String = chars_from_reader("héllo ✓".as_bytes()).collect();
+        assert_eq!(chars, "héllo ✓");
+    }
+
+    #[test]
+    fn test_chars_from_reader_lossy() {
+        let chars: String = chars_from_reader(&b"a\xffb\xe2\x9c"[..]).collect();
+        assert_eq!(chars, "a\u{FFFD}b\u{FFFD}");
+    }
+
+    #[test]
+    fn test_chars_from_reader_split_sequence() {
+        // A multi-byte character split across reads must not be replaced
+        struct OneByte<'a>(&'a [u8]);
+        impl Read for OneByte<'_> {
+            fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() {
+                    return Ok(0);
+                }
+                out[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Ok(1)
+            }
+        }
+
+        let chars: String = chars_from_reader(OneByte("é✓".as_bytes())).collect();
+        assert_eq!(chars, "é✓");
+    }
+
+    #[cfg(feature = "parquet")]
+    fn read_parquet(items_written: impl FnOnce(&mut File)) -> Vec<String> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = std::env::temp_dir().join(format!(
+            "lob-parquet-test-{}-{:?}.parquet",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let mut file = File::create(&path).unwrap();
+        items_written(&mut file);
+        drop(file);
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let mut rows = vec![reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|c| format!("{}:{}", c.name(), c.physical_type()))
+            .collect::<Vec<_>>()
+            .join(",")];
+        rows.extend(
+            reader
+                .get_row_iter(None)
+                .unwrap()
+                .map(|row| row.unwrap().to_string()),
+        );
+        let _ = std::fs::remove_file(&path);
+        rows
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_round_trip_maps() {
+        let rows = vec![
+            HashMap::from([
+                ("name".to_string(), "ann".to_string()),
+                ("age".to_string(), "31".to_string()),
+            ]),
+            HashMap::from([("name".to_string(), "bob".to_string())]),
+        ];
+        let read = read_parquet(|f| write_parquet(&rows, f).unwrap());
+        assert_eq!(read[0], "age:BYTE_ARRAY,name:BYTE_ARRAY");
+        assert_eq!(read[1], r#"{age: "31", name: "ann"}"#);
+        assert_eq!(read[2], r#"{age: null, name: "bob"}"#);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_infers_column_types() {
+        #[derive(serde::Serialize)]
+        struct Row {
+            id: i64,
+            score: f64,
+            ok: bool,
+        }
+        let rows = vec![
+            Row {
+                id: 1,
+                score: 0.5,
+                ok: true,
+            },
+            Row {
+                id: 2,
+                score: 2.0,
+                ok: false,
+            },
+        ];
+        let read = read_parquet(|f| write_parquet(&rows, f).unwrap());
+        assert_eq!(read[0], "id:INT64,ok:BOOLEAN,score:DOUBLE");
+        assert_eq!(read[1], "{id: 1, ok: true, score: 0.5}");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_scalars_use_value_column() {
+        let read = read_parquet(|f| write_parquet(&[1, 2, 3], f).unwrap());
+        assert_eq!(
+            read,
+            vec!["value:INT64", "{value: 1}", "{value: 2}", "{value: 3}"]
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_empty_input_is_valid_file() {
+        let read = read_parquet(|f| write_parquet::<i32, _>(&[], f).unwrap());
+        assert_eq!(read, vec![String::new()]);
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        let items = vec![
+            HashMap::from([("name".to_string(), "ann".to_string())]),
+            HashMap::from([("name".to_string(), "bob".to_string())]),
+        ];
+        let text = serde_yaml::to_string(&items).unwrap();
+        assert_eq!(text, "- name: ann\n- name: bob\n");
+        let parsed: Vec<HashMap<String, String>> = serde_yaml::from_str(&text).unwrap();
+        assert_eq!(parsed, items);
+    }
+
+    fn csv_string<T: serde::Serialize>(items: &[T]) -> String {
+        let mut out = Vec::new();
+        write_csv(items, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn csv_header_is_sorted_union_of_keys() {
+        let rows = vec![
+            HashMap::from([
+                ("name".to_string(), "ann".to_string()),
+                ("age".to_string(), "31".to_string()),
+            ]),
+            HashMap::from([
+                ("name".to_string(), "bob".to_string()),
+                ("city".to_string(), "oslo".to_string()),
+            ]),
+        ];
+        assert_eq!(csv_string(&rows), "age,city,name\n31,,ann\n,oslo,bob\n");
+    }
+
+    #[test]
+    fn csv_structs_and_non_string_values() {
+        #[derive(serde::Serialize)]
+        struct Row {
+            name: &'static str,
+            score: f64,
+            tag: Option<&'static str>,
+        }
+        let rows = [
+            Row {
+                name: "a",
+                score: 1.5,
+                tag: None,
+            },
+            Row {
+                name: "b, c",
+                score: 2.0,
+                tag: Some("x"),
+            },
+        ];
+        assert_eq!(
+            csv_string(&rows),
+            "name,score,tag\na,1.5,\n\"b, c\",2.0,x\n"
+        );
+    }
+
+    #[test]
+    fn csv_scalars_and_tuples_without_header() {
+        assert_eq!(csv_string(&[1, 2]), "1\n2\n");
+        assert_eq!(csv_string(&[("a", 1), ("b", 2)]), "a,1\nb,2\n");
+    }
+
+    #[test]
+    fn csv_empty_input_writes_nothing() {
+        assert_eq!(csv_string::<HashMap<String, String>>(&[]), "");
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn gzip_input_is_decompressed() {
+        let compressed = gzip(b"first\n\n  second  \n");
+        let reader = maybe_gunzip(io::Cursor::new(compressed));
+        assert_eq!(lines_from_reader(reader).to_list(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn plain_input_passes_through() {
+        let reader = maybe_gunzip(io::Cursor::new(b"plain\n".to_vec()));
+        assert_eq!(lines_from_reader(reader).to_list(), vec!["plain"]);
+    }
+
+    #[test]
+    fn gzip_csv_is_decompressed() {
+        let reader = maybe_gunzip(io::Cursor::new(gzip(b"a,b\n1,2\n")));
+        let rows = parse_csv_reader(reader).to_list();
+        assert_eq!(rows[0]["b"], "2");
+    }
+
+    #[test]
+    fn mixed_plain_and_gzip_files() {
+        let dir = std::env::temp_dir().join(format!("lob-gzip-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("a.log");
+        let zipped = dir.join("b.log.gz");
+        std::fs::write(&plain, "one\ntwo\n").unwrap();
+        std::fs::write(&zipped, gzip(b"three\n")).unwrap();
+
+        let lines = input_from_files(&[plain, zipped]).to_list();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(lines, vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn delimited_pipe_line() {
+        let rows = parse_delimited_reader(&b"ann|31|oslo\nbob|\"a|b\"\n"[..], '|').to_list();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["col0"], "ann");
+        assert_eq!(rows[0]["col2"], "oslo");
+        assert_eq!(rows[1]["col1"], "a|b");
+        assert!(!rows[1].contains_key("col2"));
+    }
+
+    #[test]
+    fn delimited_semicolon_from_files() {
+        let path = std::env::temp_dir().join(format!("lob-delim-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "x;y\n").unwrap();
+        let rows = input_delimited_from_files(std::slice::from_ref(&path), ';').to_list();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(rows[0]["col1"], "y");
+    }
+
+    #[test]
+    fn fixed_width_line() {
+        let rows = parse_fixed_width_reader(&b"ann  31oslo\n\n"[..], vec![5, 2, 4]).to_list();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["col0"], "ann");
+        assert_eq!(rows[0]["col1"], "31");
+        assert_eq!(rows[0]["col2"], "oslo");
+    }
+
+    #[test]
+    fn fixed_width_ragged_lines() {
+        // Short lines are padded with empty fields, long lines truncated
+        assert_eq!(split_fixed_width("ab", &[1, 3, 2]), vec!["a", "b", ""]);
+        assert_eq!(split_fixed_width("abcdefgh", &[2, 2]), vec!["ab", "cd"]);
+        assert_eq!(split_fixed_width("éé x", &[2, 2]), vec!["éé", "x"]);
+    }
+
+    #[test]
+    fn csv_with_and_without_header() {
+        let data = "name,age\nann,31\n";
+        let with: Vec<_> = parse_table_reader(data.as_bytes(), b',', true).collect();
+        let without: Vec<_> = parse_table_reader(data.as_bytes(), b',', false).collect();
+
+        assert_eq!(with.len(), 1);
+        let mut keys: Vec<_> = with[0].keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["age", "name"]);
+
+        assert_eq!(without.len(), 2);
+        let mut keys: Vec<_> = without[0].keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["col0", "col1"]);
+        assert_eq!(without[0]["col0"], "name");
+        assert_eq!(without[1]["col1"], "31");
+    }
+
+    #[test]
+    fn tsv_without_header_from_files() {
+        let path = std::env::temp_dir().join(format!("lob-noheader-{}.tsv", std::process::id()));
+        std::fs::write(&path, "a\tb\nc\td\n").unwrap();
+        let rows = input_tsv_headerless_from_files(std::slice::from_ref(&path)).to_list();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["col1"], "d");
+    }
+
+    #[test]
+    fn input_files_round_trip_through_env() {
+        let paths = vec![
+            std::path::PathBuf::from("/tmp/a b.txt"),
+            std::path::PathBuf::from("rel/c.csv"),
+        ];
+        std::env::set_var(INPUT_FILES_ENV, std::env::join_paths(&paths).unwrap());
+        let read = input_files();
+        std::env::remove_var(INPUT_FILES_ENV);
+        assert_eq!(read, paths);
+        assert!(input_files().is_empty());
+    }
+
+    #[test]
+    fn split_fields_on_space() {
+        assert_eq!(split_fields("a b c", ' '), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn split_fields_keeps_empties() {
+        assert_eq!(split_fields(" a b ", ' '), vec!["", "a", "b", ""]);
+        assert_eq!(split_fields("a||b", '|'), vec!["a", "", "b"]);
+        assert_eq!(split_fields("", ','), vec![""]);
+    }
+
+    #[test]
+    fn split_reader_skips_blank_lines_and_crlf() {
+        let rows: Vec<_> = split_reader(&b"x;y\r\n\n;z\n"[..], ';').collect();
+        assert_eq!(rows, vec![vec!["x", "y"], vec!["", "z"]]);
+    }
+
+    #[test]
+    fn test_parse_json_lines_strict_reports_invalid() {
+        use std::io::Cursor;
+        let data = "{\"a\": 1}\nnot json\n\n{\"a\": 2}\n";
+
+        let result: Vec<_> = parse_json_reader_strict(Cursor::new(data)).collect();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].as_ref().unwrap()["a"], 1);
+        assert!(result[1].as_ref().unwrap_err().starts_with("line 2: "));
+        assert_eq!(result[2].as_ref().unwrap()["a"], 2);
+    }
 }
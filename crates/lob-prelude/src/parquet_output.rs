@@ -0,0 +1,176 @@
+//! Parquet output (requires the `parquet` feature)
+//!
+//! Rows are serialized through `serde_json::Value`: object fields become
+//! columns (sorted by name), anything else is written to a single `value`
+//! column. Column types are inferred from the data.
+
+use parquet::basic::{ConvertedType, Repetition, Type as PhysicalType};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::errors::Result;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// Inferred type of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    Str,
+}
+
+impl ColumnType {
+    /// Narrowest type holding every non-null value
+    fn infer<'a>(values: impl Iterator<Item = &'a Value>) -> Self {
+        let mut ty = None;
+        for value in values {
+            let this = match value {
+                Value::Null => continue,
+                Value::Bool(_) => Self::Bool,
+                Value::Number(n) if n.is_i64() => Self::Int,
+                Value::Number(_) => Self::Float,
+                _ => Self::Str,
+            };
+            ty = Some(match (ty, this) {
+                (None, t) => t,
+                (Some(a), b) if a == b => a,
+                (Some(Self::Int | Self::Float), Self::Int | Self::Float) => Self::Float,
+                _ => Self::Str,
+            });
+        }
+        ty.unwrap_or(Self::Str)
+    }
+
+    fn schema_field(self, name: &str) -> Result<Type> {
+        let physical = match self {
+            Self::Int => PhysicalType::INT64,
+            Self::Float => PhysicalType::DOUBLE,
+            Self::Bool => PhysicalType::BOOLEAN,
+            Self::Str => PhysicalType::BYTE_ARRAY,
+        };
+        let mut builder =
+            Type::primitive_type_builder(name, physical).with_repetition(Repetition::OPTIONAL);
+        if self == Self::Str {
+            builder = builder.with_converted_type(ConvertedType::UTF8);
+        }
+        builder.build()
+    }
+}
+
+/// Turn an item into a column-name → value map
+fn to_row<T: serde::Serialize>(item: &T) -> Map<String, Value> {
+    match serde_json::to_value(item).unwrap_or(Value::Null) {
+        Value::Object(map) => map,
+        other => Map::from_iter([("value".to_string(), other)]),
+    }
+}
+
+/// Write items as a Parquet file
+///
+/// Empty input produces a valid file with an empty schema.
+///
+/// # Errors
+///
+/// Returns an error if the Parquet writer or the underlying writer fails.
+pub fn write_parquet<T, W>(items: &[T], writer: W) -> Result<()>
+where
+    T: serde::Serialize,
+    W: Write + Send,
+{
+    let rows: Vec<Map<String, Value>> = items.iter().map(to_row).collect();
+    let names: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|r| r.keys())
+        .map(String::as_str)
+        .collect();
+    let columns: Vec<(&str, ColumnType)> = names
+        .into_iter()
+        .map(|name| {
+            let ty = ColumnType::infer(rows.iter().filter_map(|r| r.get(name)));
+            (name, ty)
+        })
+        .collect();
+
+    let fields = columns
+        .iter()
+        .map(|(name, ty)| ty.schema_field(name).map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    let schema = Type::group_type_builder("schema")
+        .with_fields(fields)
+        .build()?;
+
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut file = SerializedFileWriter::new(writer, Arc::new(schema), props)?;
+
+    if !rows.is_empty() {
+        let mut row_group = file.next_row_group()?;
+        for (name, ty) in &columns {
+            let cells: Vec<Option<&Value>> = rows
+                .iter()
+                .map(|r| r.get(*name).filter(|v| !v.is_null()))
+                .collect();
+            let def_levels: Vec<i16> = cells.iter().map(|c| i16::from(c.is_some())).collect();
+            let present = cells.iter().flatten();
+
+            let Some(mut column) = row_group.next_column()? else {
+                break;
+            };
+            match ty {
+                ColumnType::Int => {
+                    let values: Vec<i64> = present.filter_map(|v| v.as_i64()).collect();
+                    column
+                        .typed::<Int64Type>()
+                        .write_batch(&values, Some(&def_levels), None)?;
+                }
+                ColumnType::Float => {
+                    let values: Vec<f64> = present.filter_map(|v| v.as_f64()).collect();
+                    column
+                        .typed::<DoubleType>()
+                        .write_batch(&values, Some(&def_levels), None)?;
+                }
+                ColumnType::Bool => {
+                    let values: Vec<bool> = present.filter_map(|v| v.as_bool()).collect();
+                    column
+                        .typed::<BoolType>()
+                        .write_batch(&values, Some(&def_levels), None)?;
+                }
+                ColumnType::Str => {
+                    let values: Vec<ByteArray> = present
+                        .map(|v| match v {
+                            Value::String(s) => ByteArray::from(s.as_str()),
+                            other => ByteArray::from(other.to_string().as_str()),
+                        })
+                        .collect();
+                    column.typed::<ByteArrayType>().write_batch(
+                        &values,
+                        Some(&def_levels),
+                        None,
+                    )?;
+                }
+            }
+            column.close()?;
+        }
+        row_group.close()?;
+    }
+
+    file.close()?;
+    Ok(())
+}
+
+/// Output data as a Parquet byte stream on stdout
+pub fn output_parquet<T: serde::Serialize>(items: &[T]) {
+    // The Parquet writer needs `Send`, which the stdout lock is not
+    let mut buffer = Vec::new();
+    if let Err(e) = write_parquet(items, &mut buffer) {
+        eprintln!("lob: failed to write parquet: {e}");
+        return;
+    }
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(&buffer);
+    let _ = stdout.flush();
+}